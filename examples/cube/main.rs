@@ -3,10 +3,10 @@ use std::f32::consts::PI;
 use glam::Vec3;
 
 use eng::{
-    ecs::{CommandList, World},
+    ecs::{CommandList, Query, World},
     render::{
         ArcballCameraController, CameraComponent, Material, Mesh, PerspectiveCamera, RenderBundle, Renderer, SimpleVertex, Texture, TextureFormat,
-        Transform,
+        Transform, TransformComponent,
     },
     App,
 };
@@ -57,15 +57,10 @@ async fn setup(world: &World) -> CommandList {
         .create_entity((CameraComponent { camera: Box::new(camera) },))
 }
 
-fn update(_: &World) -> CommandList {
-    // TODO
-    /*
-    let transforms = world.components_mut::<TransformComponent>();
-
-    for (_, transform) in transforms {
+fn update(mut transforms: Query<&mut TransformComponent>) -> CommandList {
+    for mut transform in transforms.iter_mut() {
         transform.transform.rotate(Vec3::new(0.0, 0.01, 0.0));
     }
-    */
 
     CommandList::new()
 }
@@ -74,7 +69,15 @@ fn update(_: &World) -> CommandList {
 async fn main() {
     pretty_env_logger::init();
 
-    App::new().await.setup(setup).await.add_system(update).run().await
+    App::builder()
+        .title("cube")
+        .build()
+        .await
+        .setup(setup)
+        .await
+        .add_system(update)
+        .run()
+        .await;
 }
 
 // Copied from https://github.com/gfx-rs/wgpu-rs/blob/master/examples/cube/main.rs#L23
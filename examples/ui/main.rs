@@ -64,7 +64,7 @@ async fn setup(world: &World) -> CommandList {
 async fn main() {
     pretty_env_logger::init();
 
-    App::new().await.setup(setup).await.run().await
+    App::new().await.setup(setup).await.run().await;
 }
 
 // Copied from https://github.com/gfx-rs/wgpu-rs/blob/master/examples/cube/main.rs#L23
@@ -1,57 +1,345 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use futures::future::LocalBoxFuture;
+use glam::Vec4;
+use hashbrown::HashSet;
 use windowing::Window;
 
-use super::{ecs, render};
+use super::{
+    ecs, input,
+    plugin::{DefaultPlugins, Plugin, PluginGroup},
+    render::{PowerPreference, PresentMode, RenderConfig},
+};
+
+// async part of plugin initialization, run before `App::setup` or `App::run`
+pub type PluginInit = for<'a> fn(&'a mut App) -> LocalBoxFuture<'a, ()>;
+
+// send to end `App::run` after the current frame
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum AppExit {
+    Success,
+    Error(i32),
+}
+
+impl AppExit {
+    // process exit code
+    pub fn code(&self) -> i32 {
+        match self {
+            AppExit::Success => 0,
+            AppExit::Error(x) => *x,
+        }
+    }
+}
+
+pub struct AppConfig {
+    pub title: String,
+    // renders into offscreen target without creating window
+    pub headless: bool,
+    pub render: RenderConfig,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            title: "eng".to_string(),
+            headless: false,
+            render: RenderConfig::default(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct AppBuilder {
+    config: AppConfig,
+}
+
+impl AppBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(config: AppConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.config.title = title.to_string();
+
+        self
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.config.render.width = width;
+        self.config.render.height = height;
+
+        self
+    }
+
+    pub fn present_mode(mut self, present_mode: PresentMode) -> Self {
+        self.config.render.present_mode = present_mode;
+
+        self
+    }
+
+    pub fn clear_color(mut self, clear_color: Vec4) -> Self {
+        self.config.render.clear_color = clear_color;
+
+        self
+    }
+
+    pub fn power_preference(mut self, power_preference: PowerPreference) -> Self {
+        self.config.render.power_preference = power_preference;
+
+        self
+    }
+
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.config.headless = headless;
+
+        self
+    }
+
+    pub async fn build(self) -> App {
+        App::with_config(self.config).await
+    }
+
+    // builds app without `DefaultPlugins`
+    pub async fn build_empty(self) -> App {
+        App::empty(self.config).await
+    }
+}
 
 pub struct App {
-    window: Window,
+    window: Option<Window>,
     world: ecs::World,
+    config: AppConfig,
+    plugins: HashSet<&'static str>,
+    pending_init: Vec<PluginInit>,
 }
 
 impl App {
     pub async fn new() -> Self {
-        let window = Window::new(1920, 1080, "test").await;
+        Self::with_config(AppConfig::default()).await
+    }
 
-        let renderer = render::Renderer::new(&window, 1920, 1080).await;
-        let asset_loader = render::AssetLoader::new();
+    pub fn builder() -> AppBuilder {
+        AppBuilder::new()
+    }
+
+    pub async fn with_config(config: AppConfig) -> Self {
+        Self::empty(config).await.add_plugins(DefaultPlugins)
+    }
+
+    // creates app with window but without any plugin
+    pub async fn empty(config: AppConfig) -> Self {
+        let window = if config.headless {
+            None
+        } else {
+            Some(Window::new(config.render.width as _, config.render.height as _, &config.title).await)
+        };
 
         let mut world = ecs::World::new();
-        world.add_resource(renderer);
-        world.add_resource(asset_loader);
+        world.add_event::<AppExit>();
+
+        Self {
+            window,
+            world,
+            config,
+            plugins: HashSet::new(),
+            pending_init: Vec::new(),
+        }
+    }
+
+    pub fn config(&self) -> &AppConfig {
+        &self.config
+    }
+
+    pub fn window(&self) -> Option<&Window> {
+        self.window.as_ref()
+    }
+
+    pub fn world(&self) -> &ecs::World {
+        &self.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut ecs::World {
+        &mut self.world
+    }
+
+    pub fn add_plugin<T: Plugin>(mut self, plugin: T) -> Self {
+        self.insert_plugin(plugin);
+
+        self
+    }
+
+    pub fn add_plugins<T: PluginGroup>(mut self, group: T) -> Self {
+        for plugin in group.plugins() {
+            self.insert_boxed_plugin(plugin);
+        }
+
+        self
+    }
+
+    // builds plugin unless plugin with same name is already added. used by plugins depending on other plugins.
+    pub fn insert_plugin<T: Plugin>(&mut self, plugin: T) {
+        self.insert_boxed_plugin(Box::new(plugin));
+    }
 
-        Self { window, world }
+    pub fn add_async_init(&mut self, init: PluginInit) {
+        self.pending_init.push(init);
+    }
+
+    fn insert_boxed_plugin(&mut self, plugin: Box<dyn Plugin>) {
+        if !self.plugins.insert(plugin.name()) {
+            log::debug!("plugin {} is already added", plugin.name());
+
+            return;
+        }
+
+        plugin.build(self);
+    }
+
+    async fn initialize_plugins(&mut self) {
+        while !self.pending_init.is_empty() {
+            for init in core::mem::take(&mut self.pending_init) {
+                init(self).await;
+            }
+        }
     }
 
     pub fn add_system<T, P>(mut self, system: T) -> Self
     where
-        T: ecs::IntoSystem<P>,
+        T: ecs::IntoSystemDescriptor<P>,
     {
         self.world.add_system(system);
 
         self
     }
 
+    pub fn add_system_to_stage<T, P>(mut self, stage: ecs::Stage, system: T) -> Self
+    where
+        T: ecs::IntoSystemDescriptor<P>,
+    {
+        self.world.add_system_to_stage(stage, system);
+
+        self
+    }
+
     pub async fn setup<F>(mut self, setup_fn: F) -> Self
     where
         F: for<'a> ecs::AsyncSingleArgFnOnce<&'a ecs::World, Output = ecs::CommandList>,
     {
+        self.initialize_plugins().await;
         self.world.setup(setup_fn).await;
 
         self
     }
 
-    pub async fn run(mut self) {
-        loop {
-            let events = self.window.next_events(false).await;
-            for _ in events {
-                // TODO
+    pub fn on_startup<T, P>(self, system: T) -> Self
+    where
+        T: ecs::IntoSystemDescriptor<P>,
+    {
+        self.add_system_to_stage(ecs::Stage::Startup, system)
+    }
+
+    pub fn on_shutdown<T, P>(self, system: T) -> Self
+    where
+        T: ecs::IntoSystemDescriptor<P>,
+    {
+        self.add_system_to_stage(ecs::Stage::Shutdown, system)
+    }
+
+    pub fn add_state<S: ecs::States>(mut self, initial: S) -> Self {
+        self.world.add_state(initial);
+
+        self
+    }
+
+    pub fn on_enter<S, T, P>(mut self, state: S, system: T) -> Self
+    where
+        S: ecs::States,
+        T: ecs::IntoSystemDescriptor<P>,
+    {
+        self.world.add_system_on_enter(state, system);
+
+        self
+    }
+
+    pub fn on_exit<S, T, P>(mut self, state: S, system: T) -> Self
+    where
+        S: ecs::States,
+        T: ecs::IntoSystemDescriptor<P>,
+    {
+        self.world.add_system_on_exit(state, system);
+
+        self
+    }
+
+    // runs until `AppExit` is sent
+    pub async fn run(self) -> AppExit {
+        self.run_loop(None).await
+    }
+
+    // runs `frames` frames, or until `AppExit` is sent
+    pub async fn run_frames(self, frames: u64) -> AppExit {
+        self.run_loop(Some(frames)).await
+    }
+
+    async fn run_loop(mut self, frames: Option<u64>) -> AppExit {
+        self.initialize_plugins().await;
+        self.world.run_stage(ecs::Stage::Startup).await;
+
+        let mut frame = 0;
+        let exit = loop {
+            if frames.is_some_and(|x| frame >= x) {
+                break AppExit::Success;
+            }
+
+            // windowing has no close event to map to `AppExit`. closing the window ends the process without running
+            // `Shutdown` stage, so apps needing clean shutdown should send `AppExit` themselves.
+            // it doesn't report suspend/resume or visibility either, so there are no lifecycle events for them. apps
+            // pausing on e.g. browser tab switch should watch `input::FocusEvent` instead.
+            if let Some(window) = &self.window {
+                for event in window.next_events(false).await {
+                    input::send_window_event(&mut self.world, event);
+                }
             }
 
             self.world.update().await;
+            frame += 1;
 
-            let mut renderer = self.world.take_resource::<render::Renderer>().unwrap();
-            renderer.render_world(&self.world);
+            if let Some(exit) = exit_requested(&self.world) {
+                break exit;
+            }
+        };
 
-            self.world.add_resource(renderer);
-        }
+        self.world.run_stage(ecs::Stage::Shutdown).await;
+
+        exit
+    }
+}
+
+fn exit_requested(world: &ecs::World) -> Option<AppExit> {
+    world.resource::<ecs::Events<AppExit>>().and_then(|x| x.iter().next().cloned())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_exit_requested() {
+        let mut world = ecs::World::new();
+        world.add_event::<AppExit>();
+        world.add_system(|_: &ecs::World| ecs::CommandList::new().add(|world: &mut ecs::World| world.send_event(AppExit::Error(2))));
+
+        assert_eq!(exit_requested(&world), None);
+
+        world.update().await;
+        assert_eq!(exit_requested(&world).map(|x| x.code()), Some(2));
     }
 }
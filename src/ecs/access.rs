@@ -0,0 +1,101 @@
+use alloc::vec::Vec;
+use core::any::TypeId;
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum AccessType {
+    Component(TypeId),
+    Resource(TypeId),
+}
+
+// set of world data a system or system input reads and writes
+#[derive(Clone, Default)]
+pub struct Access {
+    reads: Vec<AccessType>,
+    writes: Vec<AccessType>,
+    reads_all: bool,
+    exclusive: bool,
+}
+
+impl Access {
+    pub fn read(&mut self, access_type: AccessType) {
+        self.reads.push(access_type);
+    }
+
+    pub fn write(&mut self, access_type: AccessType) {
+        self.writes.push(access_type);
+    }
+
+    pub fn read_all(&mut self) {
+        self.reads_all = true;
+    }
+
+    pub fn exclusive(&mut self) {
+        self.exclusive = true;
+    }
+
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    pub fn is_read_all(&self) -> bool {
+        self.reads_all
+    }
+
+    pub fn is_compatible(&self, other: &Access) -> bool {
+        if self.exclusive || other.exclusive {
+            return false;
+        }
+
+        if self.reads_all {
+            return other.writes.is_empty();
+        }
+        if other.reads_all {
+            return self.writes.is_empty();
+        }
+
+        !self.writes.iter().any(|x| other.reads.contains(x) || other.writes.contains(x)) && !other.writes.iter().any(|x| self.reads.contains(x))
+    }
+
+    pub fn extend(&mut self, other: &Access) {
+        self.reads.extend(other.reads.iter().cloned());
+        self.writes.extend(other.writes.iter().cloned());
+        self.reads_all |= other.reads_all;
+        self.exclusive |= other.exclusive;
+    }
+
+    // merges access of a sub-input, panicking if it conflicts with already merged ones
+    pub fn merge(&mut self, other: &Access, name: &str) {
+        assert!(self.is_compatible(other), "conflicting access in {}", name);
+
+        self.extend(other);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::any::TypeId;
+
+    use super::{Access, AccessType};
+
+    #[test]
+    fn test_compatible() {
+        let mut read = Access::default();
+        read.read(AccessType::Component(TypeId::of::<u32>()));
+
+        let mut write = Access::default();
+        write.write(AccessType::Component(TypeId::of::<u32>()));
+
+        let mut other_write = Access::default();
+        other_write.write(AccessType::Resource(TypeId::of::<u32>()));
+
+        let mut read_all = Access::default();
+        read_all.read_all();
+
+        assert!(read.is_compatible(&read));
+        assert!(!read.is_compatible(&write));
+        assert!(!write.is_compatible(&write));
+        assert!(write.is_compatible(&other_write));
+        assert!(read_all.is_compatible(&read));
+        assert!(!read_all.is_compatible(&other_write));
+    }
+}
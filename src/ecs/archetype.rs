@@ -0,0 +1,257 @@
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+
+use hashbrown::HashMap;
+
+use super::{
+    component::ComponentContainer,
+    raw_vec::RawVec,
+    tick::{ComponentTicks, Mut},
+    type_descriptor::TypeDescriptor,
+    world::ComponentType,
+    Entity,
+};
+
+pub type ArchetypeId = usize;
+
+#[derive(Clone, Copy)]
+pub(super) struct EntityLocation {
+    pub archetype: ArchetypeId,
+    pub row: usize,
+}
+
+// component values of an archetype with their change ticks. values are written through shared reference by queries,
+// so they are kept in `UnsafeCell`.
+pub struct Column {
+    data: UnsafeCell<RawVec>,
+    ticks: UnsafeCell<Vec<ComponentTicks>>,
+}
+
+// systems accessing the same column from multiple threads are serialized by access checks
+unsafe impl Sync for Column {}
+
+impl Column {
+    fn new(type_descriptor: TypeDescriptor) -> Self {
+        Self {
+            data: UnsafeCell::new(RawVec::with_type_descriptor(type_descriptor)),
+            ticks: UnsafeCell::new(Vec::new()),
+        }
+    }
+
+    pub(super) fn push<T: 'static>(&mut self, value: T, tick: u32) {
+        self.data.get_mut().push(value);
+        self.ticks.get_mut().push(ComponentTicks::new(tick));
+    }
+
+    pub(super) fn push_raw(&mut self, value_slice: &[u8], tick: u32) {
+        self.data.get_mut().push_raw(value_slice);
+        self.ticks.get_mut().push(ComponentTicks::new(tick));
+    }
+
+    pub(super) fn replace_raw(&mut self, row: usize, value_slice: &[u8], tick: u32) {
+        self.data.get_mut().replace_raw(row, value_slice);
+        self.ticks.get_mut()[row].set_changed(tick);
+    }
+
+    pub(super) fn get<T: 'static>(&self, row: usize) -> Option<&T> {
+        self.data().get(row)
+    }
+
+    // component is marked as changed when written through returned `Mut`
+    pub(super) fn get_mut<T: 'static>(&mut self, row: usize, tick: u32) -> Option<Mut<'_, T>> {
+        let ticks = self.ticks.get_mut().get_mut(row)?;
+
+        Some(Mut::new(self.data.get_mut().get_mut(row)?, ticks, tick))
+    }
+
+    pub(super) fn iter<T: 'static>(&self) -> impl Iterator<Item = &T> {
+        self.data().iter()
+    }
+
+    pub(super) fn iter_mut<T: 'static>(&mut self, tick: u32) -> impl Iterator<Item = Mut<'_, T>> {
+        self.data
+            .get_mut()
+            .iter_mut()
+            .zip(self.ticks.get_mut().iter_mut())
+            .map(move |(value, ticks)| Mut::new(value, ticks, tick))
+    }
+
+    pub(super) fn data_ptr<T: 'static>(&self) -> *const T {
+        self.data().as_ptr()
+    }
+
+    pub(super) fn ticks_ptr(&self) -> *const ComponentTicks {
+        self.ticks().as_ptr()
+    }
+
+    /// # Safety
+    /// caller should have write access to the column(see `Access`), so that no other reference to it exists while writing
+    /// through returned pointers.
+    pub(super) unsafe fn data_mut_ptr<T: 'static>(&self) -> *mut T {
+        (*self.data.get()).as_mut_ptr()
+    }
+
+    /// # Safety
+    /// same as `data_mut_ptr`.
+    pub(super) unsafe fn ticks_mut_ptr(&self) -> *mut ComponentTicks {
+        (*self.ticks.get()).as_mut_ptr()
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.data().len()
+    }
+
+    fn swap_remove(&mut self, row: usize) {
+        self.data.get_mut().swap_remove(row);
+        self.ticks.get_mut().swap_remove(row);
+    }
+
+    fn move_to(&mut self, row: usize, other: &mut Column) {
+        self.data.get_mut().move_to(row, other.data.get_mut());
+        other.ticks.get_mut().push(self.ticks.get_mut().swap_remove(row));
+    }
+
+    fn take(&mut self, row: usize, component_type: ComponentType) -> ComponentContainer {
+        self.ticks.get_mut().swap_remove(row);
+
+        let data = self.data.get_mut();
+        ComponentContainer {
+            component_type,
+            type_descriptor: data.type_descriptor().clone(),
+            data: data.take_raw(row),
+        }
+    }
+
+    fn check_change_ticks(&mut self, change_tick: u32) {
+        for ticks in self.ticks.get_mut() {
+            ticks.check_ticks(change_tick);
+        }
+    }
+
+    fn data(&self) -> &RawVec {
+        // no mutable reference to the column exists while it's shared, except writes through `data_mut_ptr`
+        unsafe { &*self.data.get() }
+    }
+
+    fn ticks(&self) -> &[ComponentTicks] {
+        unsafe { &*self.ticks.get() }
+    }
+}
+
+// table of entities sharing the same set of components. each component type is stored in its own column.
+pub struct Archetype {
+    component_types: Vec<ComponentType>,
+    entities: Vec<Entity>,
+    columns: HashMap<ComponentType, Column>,
+    add_edges: HashMap<ComponentType, ArchetypeId>,
+    remove_edges: HashMap<ComponentType, ArchetypeId>,
+}
+
+impl Archetype {
+    pub(super) fn new<'a>(component_types: Vec<ComponentType>, type_descriptors: impl Iterator<Item = &'a TypeDescriptor>) -> Self {
+        let columns = component_types
+            .iter()
+            .zip(type_descriptors)
+            .map(|(&component_type, type_descriptor)| (component_type, Column::new(type_descriptor.clone())))
+            .collect();
+
+        Self {
+            component_types,
+            entities: Vec::new(),
+            columns,
+            add_edges: HashMap::new(),
+            remove_edges: HashMap::new(),
+        }
+    }
+
+    pub fn component_types(&self) -> &[ComponentType] {
+        &self.component_types
+    }
+
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn has_component(&self, component_type: ComponentType) -> bool {
+        self.columns.contains_key(&component_type)
+    }
+
+    pub(super) fn column(&self, component_type: ComponentType) -> Option<&Column> {
+        self.columns.get(&component_type)
+    }
+
+    pub(super) fn column_mut(&mut self, component_type: ComponentType) -> Option<&mut Column> {
+        self.columns.get_mut(&component_type)
+    }
+
+    pub(super) fn entities_and_column_mut(&mut self, component_type: ComponentType) -> Option<(&[Entity], &mut Column)> {
+        Some((&self.entities, self.columns.get_mut(&component_type)?))
+    }
+
+    pub(super) fn add_edge(&self, component_type: ComponentType) -> Option<ArchetypeId> {
+        self.add_edges.get(&component_type).cloned()
+    }
+
+    pub(super) fn set_add_edge(&mut self, component_type: ComponentType, archetype: ArchetypeId) {
+        self.add_edges.insert(component_type, archetype);
+    }
+
+    pub(super) fn remove_edge(&self, component_type: ComponentType) -> Option<ArchetypeId> {
+        self.remove_edges.get(&component_type).cloned()
+    }
+
+    pub(super) fn set_remove_edge(&mut self, component_type: ComponentType, archetype: ArchetypeId) {
+        self.remove_edges.insert(component_type, archetype);
+    }
+
+    // caller should push components of the entity to every column
+    pub(super) fn push_entity(&mut self, entity: Entity) -> usize {
+        self.entities.push(entity);
+
+        self.entities.len() - 1
+    }
+
+    // removes entity at `row` dropping its components. returns entity moved into `row`, if any.
+    pub(super) fn remove(&mut self, row: usize) -> Option<Entity> {
+        for column in self.columns.values_mut() {
+            column.swap_remove(row);
+        }
+
+        self.swap_remove_entity(row)
+    }
+
+    pub(super) fn check_change_ticks(&mut self, change_tick: u32) {
+        for column in self.columns.values_mut() {
+            column.check_change_ticks(change_tick);
+        }
+    }
+
+    // moves entity at `row` to `other`. components missing in `other` are returned without being dropped, and
+    // components missing in `self` should be pushed by caller. returns the row in `other`, the entity moved into `row`,
+    // if any, and the removed components.
+    pub(super) fn move_to(&mut self, row: usize, other: &mut Archetype) -> (usize, Option<Entity>, Vec<ComponentContainer>) {
+        let mut removed = Vec::new();
+        for (component_type, column) in self.columns.iter_mut() {
+            if let Some(other_column) = other.columns.get_mut(component_type) {
+                column.move_to(row, other_column);
+            } else {
+                removed.push(column.take(row, *component_type));
+            }
+        }
+
+        let new_row = other.push_entity(self.entities[row]);
+        let moved = self.swap_remove_entity(row);
+
+        (new_row, moved, removed)
+    }
+
+    fn swap_remove_entity(&mut self, row: usize) -> Option<Entity> {
+        self.entities.swap_remove(row);
+
+        self.entities.get(row).cloned()
+    }
+}
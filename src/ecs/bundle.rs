@@ -7,30 +7,36 @@ pub trait ComponentBundle {
     fn to_component_containers(self) -> Vec<ComponentContainer>;
 }
 
-impl<T1> ComponentBundle for (T1,)
-where
-    T1: 'static + Component,
-{
-    fn add_components(self, world: &mut World, entity: Entity) {
-        world.add_component(entity, self.0);
-    }
-
-    fn to_component_containers(self) -> Vec<ComponentContainer> {
-        vec![ComponentContainer::new(self.0)]
-    }
+macro_rules! impl_component_bundle {
+    ($($component: ident),*) => {
+        #[allow(non_snake_case)]
+        impl<$($component),*> ComponentBundle for ($($component,)*)
+        where
+            $($component: 'static + Component),*
+        {
+            fn add_components(self, world: &mut World, entity: Entity) {
+                let ($($component,)*) = self;
+
+                $(world.add_component(entity, $component);)*
+            }
+
+            fn to_component_containers(self) -> Vec<ComponentContainer> {
+                let ($($component,)*) = self;
+
+                vec![$(ComponentContainer::new($component)),*]
+            }
+        }
+    };
 }
 
-impl<T1, T2> ComponentBundle for (T1, T2)
-where
-    T1: 'static + Component,
-    T2: 'static + Component,
-{
-    fn add_components(self, world: &mut World, entity: Entity) {
-        world.add_component(entity, self.0);
-        world.add_component(entity, self.1);
-    }
-
-    fn to_component_containers(self) -> Vec<ComponentContainer> {
-        vec![ComponentContainer::new(self.0), ComponentContainer::new(self.1)]
-    }
+macro_rules! impl_component_bundles {
+    ($first: ident) => {
+        impl_component_bundle!($first);
+    };
+    ($first: ident, $($rest: ident),*) => {
+        impl_component_bundle!($first, $($rest),*);
+        impl_component_bundles!($($rest),*);
+    };
 }
+
+impl_component_bundles!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16);
@@ -1,15 +1,59 @@
-use super::{component::ComponentContainer, world::ComponentType, Component, ComponentBundle, Entity};
+use alloc::{boxed::Box, vec::Vec};
 
-pub(super) enum Command {
-    CreateEntity(Vec<ComponentContainer>),
-    DestroyEntity(Entity),
-    CreateComponent(Entity, Vec<ComponentContainer>),
-    DestroyComponent(Vec<ComponentType>),
+use super::{component::ComponentContainer, world::ComponentType, Component, ComponentBundle, Entity, HierarchyExt, World};
+
+// deferred world mutation. commands are applied in order at the end of the stage which issued them.
+pub trait Command: Send + 'static {
+    fn apply(self: Box<Self>, world: &mut World);
+}
+
+impl<F> Command for F
+where
+    F: FnOnce(&mut World) + Send + 'static,
+{
+    fn apply(self: Box<Self>, world: &mut World) {
+        (*self)(world)
+    }
+}
+
+struct CreateEntity(Vec<ComponentContainer>);
+
+impl Command for CreateEntity {
+    fn apply(self: Box<Self>, world: &mut World) {
+        let entity = world.spawn().entity();
+
+        world.add_components_raw(entity, self.0);
+    }
+}
+
+struct DestroyEntity(Entity);
+
+impl Command for DestroyEntity {
+    fn apply(self: Box<Self>, world: &mut World) {
+        world.destroy(self.0);
+    }
+}
+
+struct CreateComponent(Entity, Vec<ComponentContainer>);
+
+impl Command for CreateComponent {
+    fn apply(self: Box<Self>, world: &mut World) {
+        world.add_components_raw(self.0, self.1);
+    }
+}
+
+struct DestroyComponent(Entity, Vec<ComponentType>);
+
+impl Command for DestroyComponent {
+    fn apply(self: Box<Self>, world: &mut World) {
+        // removed components are dropped with their containers
+        world.remove_components_raw(self.0, &self.1);
+    }
 }
 
 #[derive(Default)]
 pub struct CommandList {
-    pub(super) commands: Vec<Command>,
+    pub(crate) commands: Vec<Box<dyn Command>>,
 }
 
 impl CommandList {
@@ -17,34 +61,100 @@ impl CommandList {
         Self::default()
     }
 
-    pub fn create_entity<T>(mut self, bundle: T) -> Self
+    #[allow(clippy::should_implement_trait)]
+    pub fn add<T>(mut self, command: T) -> Self
     where
-        T: ComponentBundle,
+        T: Command,
     {
-        self.commands.push(Command::CreateEntity(bundle.to_component_containers()));
+        self.commands.push(Box::new(command));
 
         self
     }
 
-    pub fn destroy_entity(mut self, entity: Entity) -> Self {
-        self.commands.push(Command::DestroyEntity(entity));
+    pub fn create_entity<T>(self, bundle: T) -> Self
+    where
+        T: ComponentBundle,
+    {
+        self.add(CreateEntity(bundle.to_component_containers()))
+    }
 
-        self
+    pub fn destroy_entity(self, entity: Entity) -> Self {
+        self.add(DestroyEntity(entity))
     }
 
-    pub fn create_component<T>(mut self, entity: Entity, component: T) -> Self
+    // adds components to existing or reserved entity
+    pub fn add_bundle<T>(self, entity: Entity, bundle: T) -> Self
+    where
+        T: ComponentBundle,
+    {
+        self.add(CreateComponent(entity, bundle.to_component_containers()))
+    }
+
+    pub fn create_component<T>(self, entity: Entity, component: T) -> Self
     where
         T: Component + 'static,
     {
-        self.commands
-            .push(Command::CreateComponent(entity, vec![ComponentContainer::new(component)]));
+        self.add(CreateComponent(entity, vec![ComponentContainer::new(component)]))
+    }
 
-        self
+    pub fn remove_component<T>(self, entity: Entity) -> Self
+    where
+        T: Component + 'static,
+    {
+        self.add(DestroyComponent(entity, vec![ComponentContainer::to_component_type::<T>()]))
+    }
+
+    pub fn add_child(self, entity: Entity, child: Entity) -> Self {
+        self.add(move |world: &mut World| {
+            world.add_child(entity, child);
+        })
+    }
+
+    pub fn remove_child(self, entity: Entity, child: Entity) -> Self {
+        self.add(move |world: &mut World| {
+            world.remove_child(entity, child);
+        })
+    }
+
+    pub fn despawn_recursive(self, entity: Entity) -> Self {
+        self.add(move |world: &mut World| world.despawn_recursive(entity))
     }
 
-    pub fn destroy_component(mut self, components: Vec<ComponentType>) -> Self {
-        self.commands.push(Command::DestroyComponent(components));
+    pub fn extend(mut self, other: CommandList) -> Self {
+        self.commands.extend(other.commands);
 
         self
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_custom_command() {
+        struct TestResource {
+            a: u32,
+        }
+
+        struct AddResource(u32);
+
+        impl Command for AddResource {
+            fn apply(self: Box<Self>, world: &mut World) {
+                world.add_resource(TestResource { a: self.0 });
+            }
+        }
+
+        let mut world = World::new();
+
+        world.add_system(|_: &World| {
+            CommandList::new()
+                .add(AddResource(1))
+                .add(|world: &mut World| world.resource_mut::<TestResource>().unwrap().a += 1)
+        });
+
+        world.update().await;
+
+        assert_eq!(world.resource::<TestResource>().unwrap().a, 2);
+    }
+}
@@ -1,9 +1,10 @@
-use core::{any::TypeId, mem::size_of, slice};
+use core::{any::TypeId, mem::size_of, ptr, slice};
 
 use super::type_descriptor::TypeDescriptor;
 
 pub type ComponentType = TypeId;
-pub trait Component {}
+// components are accessed from systems running on other threads
+pub trait Component: Send + Sync {}
 
 pub struct ComponentContainer {
     pub component_type: ComponentType,
@@ -26,4 +27,25 @@ impl ComponentContainer {
     pub fn to_component_type<T: Component + 'static>() -> ComponentType {
         TypeId::of::<T>()
     }
+
+    pub(super) fn into_inner<T: Component + 'static>(mut self) -> T {
+        assert!(self.component_type == Self::to_component_type::<T>());
+
+        let data = self.take_data();
+        unsafe { ptr::read_unaligned(data.as_ptr() as *const T) }
+    }
+
+    // takes raw bytes of the component, leaving container empty. caller becomes responsible for dropping the component.
+    pub(super) fn take_data(&mut self) -> Box<[u8]> {
+        core::mem::take(&mut self.data)
+    }
+}
+
+impl Drop for ComponentContainer {
+    fn drop(&mut self) {
+        // data is empty if it's taken or component is zero sized
+        if !self.data.is_empty() {
+            (self.type_descriptor.drop)(&mut self.data);
+        }
+    }
 }
@@ -1,46 +1,172 @@
+use alloc::vec::Vec;
 use core::{any::Any, marker::PhantomData};
 
-use super::{system::SystemInput, World};
+use super::{
+    access::{Access, AccessType},
+    system::SystemInput,
+    tick::{ComponentTicks, SystemTicks},
+    World,
+};
 
-#[derive(Eq, PartialEq)]
-pub enum KeyboardEvent {
-    KeyDown(u8),
-    KeyUp(u8),
+struct EventInstance<T> {
+    event: T,
+    ticks: ComponentTicks,
 }
 
-pub struct Event<'a, T>
+// double buffered event queue. events are kept until the end of the frame after the one they were sent in,
+// so every system gets a chance to read them regardless of ordering.
+pub struct Events<T> {
+    previous: Vec<EventInstance<T>>,
+    current: Vec<EventInstance<T>>,
+}
+
+impl<T> Events<T> {
+    pub fn new() -> Self {
+        Self {
+            previous: Vec::new(),
+            current: Vec::new(),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.instances().map(|x| &x.event)
+    }
+
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(super) fn send(&mut self, event: T, tick: u32) {
+        self.current.push(EventInstance {
+            event,
+            ticks: ComponentTicks::new(tick),
+        });
+    }
+
+    // drops events of previous frame
+    pub(super) fn update(&mut self) {
+        self.previous = core::mem::take(&mut self.current);
+    }
+
+    pub(super) fn check_change_ticks(&mut self, change_tick: u32) {
+        for instance in self.previous.iter_mut().chain(self.current.iter_mut()) {
+            instance.ticks.check_ticks(change_tick);
+        }
+    }
+
+    fn instances(&self) -> impl Iterator<Item = &EventInstance<T>> {
+        self.previous.iter().chain(self.current.iter())
+    }
+}
+
+impl<T> Default for Events<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// reads events sent since the system last ran
+pub struct EventReader<'a, T>
 where
     T: 'static,
 {
-    world: &'a World,
-    _phantom: PhantomData<T>,
+    events: Option<&'a Events<T>>,
+    ticks: SystemTicks,
 }
 
-impl<'a, T> SystemInput for Event<'a, T> {
-    type ActualInput<'i> = Event<'i, T>;
+impl<'a, T> SystemInput for EventReader<'a, T>
+where
+    T: Send + Sync,
+{
+    type ActualInput<'i> = EventReader<'i, T>;
 
     fn is_available(_: &World) -> bool {
         true
     }
 
-    fn new<'w>(world: &'w World, _: Option<&dyn Any>) -> Self::ActualInput<'w> {
+    fn access(access: &mut Access) {
+        access.read(AccessType::Resource(World::get_resource_type::<Events<T>>()));
+    }
+
+    fn new<'w>(world: &'w World, ticks: SystemTicks, _: Option<&dyn Any>) -> Self::ActualInput<'w> {
+        Self::ActualInput::<'w> {
+            events: world.resource::<Events<T>>(),
+            ticks,
+        }
+    }
+}
+
+impl<'a, T> EventReader<'a, T> {
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let ticks = self.ticks;
+
+        self.events
+            .into_iter()
+            .flat_map(|x| x.instances())
+            .filter(move |x| x.ticks.is_added(ticks.last_run, ticks.change_tick))
+            .map(|x| &x.event)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
+
+pub struct EventWriter<'a, T>
+where
+    T: 'static,
+{
+    world: &'a World,
+    ticks: SystemTicks,
+    _phantom: PhantomData<T>,
+}
+
+impl<'a, T> SystemInput for EventWriter<'a, T>
+where
+    T: Send + Sync,
+{
+    type ActualInput<'i> = EventWriter<'i, T>;
+
+    // system doesn't run until event type is registered with `World::add_event`
+    fn is_available(world: &World) -> bool {
+        world.resource::<Events<T>>().is_some()
+    }
+
+    fn access(access: &mut Access) {
+        access.write(AccessType::Resource(World::get_resource_type::<Events<T>>()));
+    }
+
+    fn new<'w>(world: &'w World, ticks: SystemTicks, _: Option<&dyn Any>) -> Self::ActualInput<'w> {
         Self::ActualInput::<'w> {
             world,
+            ticks,
             _phantom: PhantomData,
         }
     }
 }
 
-impl<'a, T> Event<'a, T> {
-    pub fn get(&self) -> &T {
-        self.world.event::<T>().unwrap()
+impl<'a, T> EventWriter<'a, T> {
+    pub fn send(&mut self, event: T) {
+        // safety: access of Events<T> is validated to be exclusive to this system
+        let events = unsafe { self.world.resource_unchecked_mut::<Events<T>>(self.ticks.change_tick) };
+
+        events
+            .expect("event type should be registered with World::add_event")
+            .send(event, self.ticks.change_tick);
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::ecs::{CommandList, Component};
+    use crate::{
+        ecs::{CommandList, Component, IntoSystemDescriptor, ResMut},
+        input::{KeyCode, KeyboardEvent},
+    };
 
     #[tokio::test]
     async fn test_keyboard_event() {
@@ -51,15 +177,92 @@ mod test {
 
         let mut world = World::new();
 
-        world.add_system(|x: Event<KeyboardEvent>| {
-            assert!(*x.get() == KeyboardEvent::KeyDown(0));
+        world.add_system(|x: EventReader<KeyboardEvent>| {
+            assert!(x.iter().all(|x| *x == KeyboardEvent::KeyDown(KeyCode::A)));
 
-            CommandList::new().create_entity((TestComponent { a: 1 },))
+            x.iter()
+                .fold(CommandList::new(), |commands, _| commands.create_entity((TestComponent { a: 1 },)))
         });
 
-        world.on_event(KeyboardEvent::KeyDown(0));
+        world.send_event(KeyboardEvent::KeyDown(KeyCode::A));
+        world.send_event(KeyboardEvent::KeyDown(KeyCode::A));
+        world.update().await;
+        world.update().await;
+
+        assert_eq!(world.components::<TestComponent>().filter(|x| x.1.a == 1).count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_event_writer() {
+        struct TestEvent {
+            a: u32,
+        }
+        struct TestResource {
+            a: Vec<u32>,
+        }
+
+        let mut world = World::new();
+        world.add_event::<TestEvent>();
+        world.add_resource(TestResource { a: Vec::new() });
+
+        // reader is ordered before writer, so it reads events on next frame
+        world.add_system(
+            (|x: EventReader<TestEvent>, mut resource: ResMut<TestResource>| {
+                resource.get_mut().a.extend(x.iter().map(|x| x.a));
+
+                CommandList::new()
+            })
+            .before("writer"),
+        );
+        world.add_system(
+            (|mut x: EventWriter<TestEvent>| {
+                x.send(TestEvent { a: 1 });
+
+                CommandList::new()
+            })
+            .label("writer"),
+        );
+
         world.update().await;
+        assert!(world.resource::<TestResource>().unwrap().a.is_empty());
 
-        assert!(world.components::<TestComponent>().any(|x| x.1.a == 1))
+        world.update().await;
+        assert_eq!(world.resource::<TestResource>().unwrap().a, [1]);
+
+        world.update().await;
+        assert_eq!(world.resource::<TestResource>().unwrap().a, [1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_event_writer_unregistered() {
+        struct TestEvent {}
+
+        let mut world = World::new();
+        world.add_system(|mut x: EventWriter<TestEvent>| {
+            x.send(TestEvent {});
+
+            CommandList::new()
+        });
+
+        world.update().await;
+        assert!(world.resource::<Events<TestEvent>>().is_none());
+
+        world.add_event::<TestEvent>();
+        world.update().await;
+        assert_eq!(world.resource::<Events<TestEvent>>().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_events_expire() {
+        struct TestEvent {}
+
+        let mut world = World::new();
+        world.send_event(TestEvent {});
+
+        world.update().await;
+        assert_eq!(world.resource::<Events<TestEvent>>().unwrap().len(), 1);
+
+        world.update().await;
+        assert!(world.resource::<Events<TestEvent>>().unwrap().is_empty());
     }
 }
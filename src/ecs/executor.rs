@@ -0,0 +1,260 @@
+use alloc::{boxed::Box, vec, vec::Vec};
+
+use super::{
+    access::Access,
+    command::{Command, CommandList},
+    schedule::{StageSystems, SystemDescriptor},
+    system::System,
+    World,
+};
+
+// runs systems of a stage in batches. systems in a batch have compatible access and run concurrently.
+pub(super) async fn run_stage(world: &mut World, stage: &mut StageSystems) -> Vec<Box<dyn Command>> {
+    let (systems, dependencies) = stage.systems_mut();
+
+    let mut commands = Vec::new();
+    let mut done = vec![false; systems.len()];
+    loop {
+        let batch = next_batch(world, systems, dependencies, &mut done);
+        if batch.is_empty() {
+            break;
+        }
+
+        for index in &batch {
+            done[*index] = true;
+        }
+
+        if batch.len() == 1 {
+            for index in batch {
+                let system = systems[index].system_mut();
+
+                if system.access().is_exclusive() {
+                    commands.extend(system.run_exclusive(world).commands);
+                } else {
+                    commands.extend(system.run(world, None).commands);
+                }
+            }
+        } else {
+            let mut batch_systems = systems
+                .iter_mut()
+                .enumerate()
+                .filter(|(index, _)| batch.contains(index))
+                .map(|(_, x)| x.system_mut())
+                .collect::<Vec<_>>();
+
+            for result in run_parallel(world, &mut batch_systems).await {
+                commands.extend(result.commands);
+            }
+        }
+    }
+
+    commands
+}
+
+// collects systems whose dependencies are done and whose access doesn't conflict with each other.
+// systems not meeting run conditions are marked as done without running. systems conflicting with a skipped one are
+// skipped too, so conflicting systems run in insertion order.
+fn next_batch(world: &World, systems: &[SystemDescriptor], dependencies: &[Vec<usize>], done: &mut [bool]) -> Vec<usize> {
+    let mut batch = Vec::new();
+    let mut batch_access = Access::default();
+    let mut skipped_access = Access::default();
+
+    for index in 0..systems.len() {
+        if done[index] || !dependencies[index].iter().all(|&x| done[x]) {
+            continue;
+        }
+
+        let access = systems[index].system().access();
+        if access.is_exclusive() && !batch.is_empty() {
+            break;
+        }
+        if !batch.is_empty() && (!batch_access.is_compatible(access) || !skipped_access.is_compatible(access)) {
+            skipped_access.extend(access);
+            continue;
+        }
+
+        if !systems[index].should_run(world) {
+            done[index] = true;
+            continue;
+        }
+
+        batch.push(index);
+        if access.is_exclusive() {
+            break;
+        }
+        batch_access.extend(access);
+    }
+
+    batch
+}
+
+// system running on tokio's blocking pool. systems in a batch have compatible access, and components and resources
+// accessed through system inputs are `Send + Sync`. systems reading whole world may touch other resources, so they stay
+// on the calling task.
+#[cfg(not(target_arch = "wasm32"))]
+struct SystemTask {
+    world: *const World,
+    system: *mut (dyn System + 'static),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+unsafe impl Send for SystemTask {}
+
+// waits for spawned systems before borrowed world and systems are released, even if `run_parallel` is dropped midway
+#[cfg(not(target_arch = "wasm32"))]
+struct SystemTasks(Vec<tokio::task::JoinHandle<CommandList>>);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for SystemTasks {
+    fn drop(&mut self) {
+        while !self.0.iter().all(|x| x.is_finished()) {
+            std::thread::yield_now();
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn run_parallel(world: &World, systems: &mut [&mut (dyn System + 'static)]) -> Vec<CommandList> {
+    let (local, spawned): (Vec<_>, Vec<_>) = systems.iter_mut().partition(|x| x.access().is_read_all());
+
+    let mut tasks = SystemTasks(
+        spawned
+            .into_iter()
+            .map(|system| {
+                let task = SystemTask {
+                    world,
+                    system: &mut **system,
+                };
+
+                tokio::task::spawn_blocking(move || {
+                    let task = task;
+
+                    // world and system outlive the task, as `SystemTasks` waits for it
+                    unsafe { (*task.system).run(&*task.world, None) }
+                })
+            })
+            .collect(),
+    );
+
+    let mut results = local
+        .into_iter()
+        .map(|system| std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| system.run(world, None))))
+        .collect::<Vec<_>>();
+
+    for handle in tasks.0.iter_mut() {
+        results.push(handle.await.map_err(|x| x.try_into_panic().expect("system task should not be cancelled")));
+    }
+
+    results.into_iter().map(|x| x.unwrap_or_else(|x| std::panic::resume_unwind(x))).collect()
+}
+
+// no threads on wasm32, run sequentially
+#[cfg(target_arch = "wasm32")]
+async fn run_parallel(world: &World, systems: &mut [&mut (dyn System + 'static)]) -> Vec<CommandList> {
+    systems.iter_mut().map(|x| x.run(world, None)).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecs::{CommandList, Component, IntoSystemDescriptor, Query, ResMut, Resource};
+
+    struct TestResource1 {
+        a: u32,
+    }
+    struct TestResource2 {
+        a: u32,
+    }
+
+    #[test]
+    fn test_batch() {
+        let mut world = World::new();
+        world.add_resource(TestResource1 { a: 1 });
+
+        let systems = [
+            (|_: ResMut<TestResource1>| CommandList::new()).into_descriptor(),
+            (|_: ResMut<TestResource2>| CommandList::new()).into_descriptor(),
+            (|_: Resource<TestResource1>| CommandList::new()).into_descriptor(),
+            (|_: &mut World| {}).into_descriptor(),
+            (|_: Resource<TestResource2>| CommandList::new()).into_descriptor(),
+        ];
+        let dependencies = vec![Vec::new(); systems.len()];
+        let mut done = vec![false; systems.len()];
+
+        let mut next_batch = || {
+            let batch = next_batch(&world, &systems, &dependencies, &mut done);
+            for index in &batch {
+                done[*index] = true;
+            }
+
+            batch
+        };
+
+        assert_eq!(next_batch(), [0, 1]);
+        assert_eq!(next_batch(), [2]);
+        assert_eq!(next_batch(), [3]);
+        assert_eq!(next_batch(), [4]);
+        assert!(next_batch().is_empty());
+    }
+
+    #[test]
+    fn test_batch_order() {
+        let mut world = World::new();
+        world.add_resource(TestResource1 { a: 1 });
+        world.add_resource(TestResource2 { a: 1 });
+
+        let systems = [
+            (|_: ResMut<TestResource1>| CommandList::new()).into_descriptor(),
+            (|_: Resource<TestResource1>, _: ResMut<TestResource2>| CommandList::new()).into_descriptor(),
+            (|_: Resource<TestResource2>| CommandList::new()).into_descriptor(),
+        ];
+        let dependencies = vec![Vec::new(); systems.len()];
+        let mut done = vec![false; systems.len()];
+
+        let mut next_batch = || {
+            let batch = next_batch(&world, &systems, &dependencies, &mut done);
+            for index in &batch {
+                done[*index] = true;
+            }
+
+            batch
+        };
+
+        // last system doesn't conflict with the first, but should wait for the second one
+        assert_eq!(next_batch(), [0]);
+        assert_eq!(next_batch(), [1]);
+        assert_eq!(next_batch(), [2]);
+        assert!(next_batch().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_parallel() {
+        struct TestComponent {
+            a: u32,
+        }
+        impl Component for TestComponent {}
+
+        let mut world = World::new();
+        world.add_resource(TestResource1 { a: 0 });
+        world.add_resource(TestResource2 { a: 0 });
+        world.spawn().with(TestComponent { a: 1 }).entity();
+        world.spawn().with(TestComponent { a: 2 }).entity();
+
+        world.add_system(|query: Query<&TestComponent>, mut resource: ResMut<TestResource1>| {
+            resource.get_mut().a = query.iter().map(|x| x.a).sum();
+
+            CommandList::new().create_entity((TestComponent { a: 3 },))
+        });
+        world.add_system(|query: Query<&TestComponent>, mut resource: ResMut<TestResource2>| {
+            resource.get_mut().a = query.iter().count() as u32;
+
+            CommandList::new().create_entity((TestComponent { a: 4 },))
+        });
+
+        world.update().await;
+
+        assert_eq!(world.resource::<TestResource1>().unwrap().a, 3);
+        assert_eq!(world.resource::<TestResource2>().unwrap().a, 2);
+        assert_eq!(world.components::<TestComponent>().count(), 4);
+    }
+}
@@ -13,10 +13,12 @@ pub struct Parent {
 impl Component for Parent {}
 
 pub trait HierarchyExt {
-    fn add_child(&mut self, entity: Entity, child: Entity);
+    // attaches `child` to `entity`, detaching it from previous parent. returns false if it would create a cycle.
+    fn add_child(&mut self, entity: Entity, child: Entity) -> bool;
     fn remove_child(&mut self, entity: Entity, child: Entity) -> bool;
     fn children(&self, entity: Entity) -> &Vec<Entity>;
     fn parent(&self, entity: Entity) -> Option<Entity>;
+    fn despawn_recursive(&mut self, entity: Entity);
 }
 
 lazy_static::lazy_static! {
@@ -24,28 +26,37 @@ lazy_static::lazy_static! {
 }
 
 impl HierarchyExt for World {
-    fn add_child(&mut self, entity: Entity, child: Entity) {
+    fn add_child(&mut self, entity: Entity, child: Entity) -> bool {
+        if !self.is_alive(entity) || !self.is_alive(child) || is_ancestor(self, child, entity) {
+            return false;
+        }
+
+        match self.parent(child) {
+            Some(x) if x == entity => return true,
+            Some(x) => {
+                remove_from_children(self, x, child);
+            }
+            None => {}
+        }
+
         let children_component = self.component_mut::<Children>(entity);
-        if let Some(x) = children_component {
+        if let Some(mut x) = children_component {
             x.children.push(child);
         } else {
             self.add_component(entity, Children { children: vec![child] });
         }
+        self.add_component(child, Parent { parent: entity });
+
+        true
     }
 
     fn remove_child(&mut self, entity: Entity, child: Entity) -> bool {
-        let children_component = self.component_mut::<Children>(entity);
-        if let Some(x) = children_component {
-            if let Some(index) = x.children.iter().position(|&x| x == child) {
-                x.children.remove(index);
-
-                true
-            } else {
-                false
-            }
-        } else {
-            false
+        if !remove_from_children(self, entity, child) {
+            return false;
         }
+        self.remove_component::<Parent>(child);
+
+        true
     }
 
     fn children(&self, entity: Entity) -> &Vec<Entity> {
@@ -62,11 +73,69 @@ impl HierarchyExt for World {
 
         Some(parent?.parent)
     }
+
+    // walks descendants with explicit stack, so deep hierarchy can't overflow call stack
+    fn despawn_recursive(&mut self, entity: Entity) {
+        let mut stack = vec![entity];
+        while let Some(entity) = stack.pop() {
+            if let Some(x) = self.remove_component::<Children>(entity) {
+                for &child in &x.children {
+                    self.remove_component::<Parent>(child);
+                }
+                stack.extend(x.children);
+            }
+
+            self.destroy(entity);
+        }
+    }
+}
+
+// detaches entity from its parent and children. called when entity is destroyed.
+pub(super) fn detach(world: &mut World, entity: Entity) {
+    if let Some(parent) = world.remove_component::<Parent>(entity) {
+        remove_from_children(world, parent.parent, entity);
+    }
+
+    if let Some(x) = world.remove_component::<Children>(entity) {
+        for child in x.children {
+            world.remove_component::<Parent>(child);
+        }
+    }
+}
+
+fn remove_from_children(world: &mut World, entity: Entity, child: Entity) -> bool {
+    let children_component = world.component_mut::<Children>(entity);
+    if let Some(mut x) = children_component {
+        if let Some(index) = x.children.iter().position(|&x| x == child) {
+            x.children.remove(index);
+
+            true
+        } else {
+            false
+        }
+    } else {
+        false
+    }
+}
+
+// returns true if `ancestor` is `entity` or one of its ancestors
+fn is_ancestor(world: &World, ancestor: Entity, entity: Entity) -> bool {
+    let mut current = Some(entity);
+    while let Some(x) = current {
+        if x == ancestor {
+            return true;
+        }
+
+        current = world.parent(x);
+    }
+
+    false
 }
 
 #[cfg(test)]
 mod test {
     use super::{HierarchyExt, World};
+    use crate::ecs::CommandList;
 
     #[test]
     fn test_add_children() {
@@ -95,4 +164,109 @@ mod test {
 
         assert_eq!(world.children(entity).len(), 0);
     }
+
+    #[test]
+    fn test_parent() {
+        let mut world = World::new();
+
+        let entity = world.spawn().entity();
+        let child = world.spawn().entity();
+
+        world.add_child(entity, child);
+        assert!(world.parent(child) == Some(entity));
+
+        world.remove_child(entity, child);
+        assert!(world.parent(child).is_none());
+    }
+
+    #[test]
+    fn test_reparent() {
+        let mut world = World::new();
+
+        let entity1 = world.spawn().entity();
+        let entity2 = world.spawn().entity();
+        let child = world.spawn().entity();
+
+        assert!(world.add_child(entity1, child));
+        assert!(world.add_child(entity2, child));
+        assert!(world.add_child(entity2, child));
+
+        assert!(world.children(entity1).is_empty());
+        assert_eq!(world.children(entity2).len(), 1);
+        assert!(world.parent(child) == Some(entity2));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut world = World::new();
+
+        let entity = world.spawn().entity();
+        let child = world.spawn().entity();
+        let grandchild = world.spawn().entity();
+
+        assert!(world.add_child(entity, child));
+        assert!(world.add_child(child, grandchild));
+
+        assert!(!world.add_child(grandchild, entity));
+        assert!(!world.add_child(entity, entity));
+        assert!(world.parent(entity).is_none());
+    }
+
+    #[test]
+    fn test_despawn_recursive() {
+        let mut world = World::new();
+
+        let root = world.spawn().entity();
+        let entity = world.spawn().entity();
+        let child = world.spawn().entity();
+        let grandchild = world.spawn().entity();
+
+        world.add_child(root, entity);
+        world.add_child(entity, child);
+        world.add_child(child, grandchild);
+
+        world.despawn_recursive(entity);
+
+        assert!(world.is_alive(root));
+        assert!(!world.is_alive(entity));
+        assert!(!world.is_alive(child));
+        assert!(!world.is_alive(grandchild));
+        assert!(world.children(root).is_empty());
+    }
+
+    #[test]
+    fn test_destroy_detaches() {
+        let mut world = World::new();
+
+        let root = world.spawn().entity();
+        let entity = world.spawn().entity();
+        let child = world.spawn().entity();
+
+        world.add_child(root, entity);
+        world.add_child(entity, child);
+
+        world.destroy(entity);
+
+        assert!(world.children(root).is_empty());
+        assert!(world.parent(child).is_none());
+    }
+
+    #[test]
+    fn test_hierarchy_command() {
+        let mut world = World::new();
+
+        let entity = world.spawn().entity();
+        let child = world.reserve_entity();
+
+        let cmd_list = CommandList::new().add_child(entity, child);
+        world.run_commands(cmd_list.commands);
+
+        assert!(world.parent(child) == Some(entity));
+
+        let cmd_list = CommandList::new().despawn_recursive(entity);
+        world.run_commands(cmd_list.commands);
+
+        assert!(!world.is_alive(entity));
+        assert!(!world.is_alive(child));
+    }
 }
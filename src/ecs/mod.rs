@@ -1,26 +1,33 @@
+mod access;
+mod archetype;
 mod builder;
 mod bundle;
 mod command;
 mod component;
 mod event;
+mod executor;
 mod hierarchy;
 mod query;
 mod raw_vec;
 mod resource;
-mod sparse_raw_vec;
+mod schedule;
+mod state;
 mod system;
+mod tick;
 mod type_descriptor;
 mod world;
 
 pub use bundle::ComponentBundle;
-pub use command::CommandList;
+pub use command::{Command, CommandList};
 pub use component::{Component, ComponentContainer};
-pub use event::Event;
-pub use event::KeyboardEvent;
-pub use hierarchy::HierarchyExt;
-pub use query::Query;
-pub use resource::Resource;
+pub use event::{EventReader, EventWriter, Events};
+pub use hierarchy::{Children, HierarchyExt, Parent};
+pub use query::{Added, Changed, Or, Query, With, Without};
+pub use resource::{ResMut, Resource};
+pub use schedule::{IntoSystemDescriptor, RunCount, Stage, SystemDescriptor, SystemLabel};
+pub use state::{in_state, NextState, State, StateScoped, States};
 pub use system::IntoSystem;
+pub use tick::Mut;
 pub use world::World;
 
 pub(crate) use world::AsyncSingleArgFnOnce;
@@ -28,4 +35,5 @@ pub(crate) use world::AsyncSingleArgFnOnce;
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub struct Entity {
     id: u32,
+    generation: u32,
 }
@@ -1,70 +1,460 @@
-use core::{any::Any, marker::PhantomData};
+use core::{
+    any::{type_name, Any},
+    marker::PhantomData,
+};
 
-use super::{system::SystemInput, Component, Entity, World};
+use super::{
+    access::{Access, AccessType},
+    archetype::Archetype,
+    system::SystemInput,
+    tick::{ComponentTicks, Mut, SystemTicks},
+    world::ComponentType,
+    Component, Entity, World,
+};
 
-pub struct Query<'a, P>
+pub struct Query<'a, P, F = ()>
 where
     P: QueryParam,
+    F: QueryFilter,
 {
     world: &'a World,
-    _phantom: PhantomData<P>,
+    ticks: SystemTicks,
+    _phantom: PhantomData<(P, F)>,
 }
 
-impl<'a, P> Query<'a, P>
+impl<'a, P, F> Query<'a, P, F>
 where
     P: QueryParam,
+    F: QueryFilter,
 {
-    pub fn new(world: &'a World) -> Self {
+    pub fn new(world: &'a World) -> Self
+    where
+        P: ReadOnlyQueryParam,
+    {
+        Self::with_world(world)
+    }
+
+    // panics if params conflict, e.g. `(&mut T, &mut T)`, same as query used as system input
+    pub fn new_mut(world: &'a mut World) -> Self {
+        <Self as SystemInput>::access(&mut Access::default());
+
+        Self::with_world(world)
+    }
+
+    fn with_world(world: &'a World) -> Self {
+        let ticks = SystemTicks {
+            last_run: 0,
+            change_tick: world.change_tick(),
+        };
+
         Self {
             world,
+            ticks,
             _phantom: PhantomData,
         }
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_
+    pub fn iter(&self) -> impl Iterator<Item = P::Item<'a>> + 'a
     where
-        P: QueryParam,
+        P: ReadOnlyQueryParam + 'a,
+        F: 'a,
     {
-        self.world.entities().filter(move |&entity| P::matches(self.world, entity))
+        // read only fetches can't alias with each other
+        unsafe { Self::iter_unchecked(self.world, self.ticks) }
+    }
+
+    pub fn iter_mut<'q>(&'q mut self) -> impl Iterator<Item = P::Item<'q>> + 'q
+    where
+        P: 'q,
+        F: 'q,
+    {
+        unsafe { Self::iter_unchecked(self.world, self.ticks) }
+    }
+
+    unsafe fn iter_unchecked<'w>(world: &'w World, ticks: SystemTicks) -> impl Iterator<Item = P::Item<'w>> + 'w
+    where
+        P: 'w,
+        F: 'w,
+    {
+        world.archetypes().filter(|x| P::matches(x) && F::matches(x)).flat_map(move |archetype| {
+            let fetch = P::fetch_init(archetype, ticks);
+            let filter = F::fetch_init(archetype, ticks);
+
+            (0..archetype.len())
+                .filter(move |&row| unsafe { F::filter(&filter, row) })
+                .map(move |row| unsafe { P::fetch(&fetch, row) })
+        })
     }
 }
 
 pub trait QueryParam {
-    fn matches(world: &World, entity: Entity) -> bool;
+    type Item<'w>;
+    type Fetch<'w>;
+
+    fn access(access: &mut Access);
+    fn matches(archetype: &Archetype) -> bool;
+    fn fetch_init(archetype: &Archetype, ticks: SystemTicks) -> Self::Fetch<'_>;
+
+    /// # Safety
+    /// `row` should be in bounds of the archetype `fetch` was created from, and mutable items should not alias.
+    unsafe fn fetch<'w>(fetch: &Self::Fetch<'w>, row: usize) -> Self::Item<'w>;
 }
 
-impl<T1> QueryParam for (T1,)
+// marker for params which only read from world
+pub trait ReadOnlyQueryParam: QueryParam {}
+
+impl QueryParam for Entity {
+    type Item<'w> = Entity;
+    type Fetch<'w> = &'w [Entity];
+
+    fn access(_: &mut Access) {}
+
+    fn matches(_: &Archetype) -> bool {
+        true
+    }
+
+    fn fetch_init(archetype: &Archetype, _: SystemTicks) -> Self::Fetch<'_> {
+        archetype.entities()
+    }
+
+    unsafe fn fetch<'w>(fetch: &Self::Fetch<'w>, row: usize) -> Self::Item<'w> {
+        *fetch.get_unchecked(row)
+    }
+}
+
+impl ReadOnlyQueryParam for Entity {}
+
+impl<T> QueryParam for &T
 where
-    T1: Component + 'static,
+    T: Component + 'static,
 {
-    fn matches(world: &World, entity: Entity) -> bool {
-        world.has_component::<T1>(entity)
+    type Item<'w> = &'w T;
+    type Fetch<'w> = *const T;
+
+    fn access(access: &mut Access) {
+        access.read(AccessType::Component(ComponentType::of::<T>()));
+    }
+
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.has_component(ComponentType::of::<T>())
+    }
+
+    fn fetch_init(archetype: &Archetype, _: SystemTicks) -> Self::Fetch<'_> {
+        archetype.column(ComponentType::of::<T>()).unwrap().data_ptr::<T>()
+    }
+
+    unsafe fn fetch<'w>(fetch: &Self::Fetch<'w>, row: usize) -> Self::Item<'w> {
+        &*fetch.add(row)
     }
 }
 
-impl<T1, T2> QueryParam for (T1, T2)
+impl<T> ReadOnlyQueryParam for &T where T: Component + 'static {}
+
+impl<T> QueryParam for &mut T
 where
-    T1: Component + 'static,
-    T2: Component + 'static,
+    T: Component + 'static,
 {
-    fn matches(world: &World, entity: Entity) -> bool {
-        world.has_component::<T1>(entity) && world.has_component::<T2>(entity)
+    type Item<'w> = Mut<'w, T>;
+    type Fetch<'w> = (*mut T, *mut ComponentTicks, u32);
+
+    fn access(access: &mut Access) {
+        access.write(AccessType::Component(ComponentType::of::<T>()));
+    }
+
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.has_component(ComponentType::of::<T>())
+    }
+
+    fn fetch_init(archetype: &Archetype, ticks: SystemTicks) -> Self::Fetch<'_> {
+        let column = archetype.column(ComponentType::of::<T>()).unwrap();
+
+        // write access to the column is checked by `access`
+        unsafe { (column.data_mut_ptr::<T>(), column.ticks_mut_ptr(), ticks.change_tick) }
+    }
+
+    unsafe fn fetch<'w>(fetch: &Self::Fetch<'w>, row: usize) -> Self::Item<'w> {
+        Mut::new(&mut *fetch.0.add(row), &mut *fetch.1.add(row), fetch.2)
     }
 }
 
-impl<'a, P> SystemInput for Query<'a, P>
+impl<P> QueryParam for Option<P>
 where
     P: QueryParam,
 {
-    type ActualInput<'i> = Query<'i, P>;
+    type Item<'w> = Option<P::Item<'w>>;
+    type Fetch<'w> = Option<P::Fetch<'w>>;
+
+    fn access(access: &mut Access) {
+        P::access(access);
+    }
+
+    fn matches(_: &Archetype) -> bool {
+        true
+    }
+
+    fn fetch_init(archetype: &Archetype, ticks: SystemTicks) -> Self::Fetch<'_> {
+        if P::matches(archetype) {
+            Some(P::fetch_init(archetype, ticks))
+        } else {
+            None
+        }
+    }
+
+    unsafe fn fetch<'w>(fetch: &Self::Fetch<'w>, row: usize) -> Self::Item<'w> {
+        fetch.as_ref().map(|x| P::fetch(x, row))
+    }
+}
+
+impl<P> ReadOnlyQueryParam for Option<P> where P: ReadOnlyQueryParam {}
+
+pub trait QueryFilter {
+    type Fetch<'w>;
+
+    fn access(access: &mut Access);
+    fn matches(archetype: &Archetype) -> bool;
+    fn fetch_init(archetype: &Archetype, ticks: SystemTicks) -> Self::Fetch<'_>;
+
+    /// # Safety
+    /// `row` should be in bounds of the archetype `fetch` was created from.
+    unsafe fn filter(fetch: &Self::Fetch<'_>, row: usize) -> bool;
+}
+
+pub struct With<T>(PhantomData<T>);
+
+impl<T> QueryFilter for With<T>
+where
+    T: Component + 'static,
+{
+    type Fetch<'w> = ();
+
+    fn access(_: &mut Access) {}
+
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.has_component(ComponentType::of::<T>())
+    }
+
+    fn fetch_init(_: &Archetype, _: SystemTicks) -> Self::Fetch<'_> {}
+
+    unsafe fn filter(_: &Self::Fetch<'_>, _: usize) -> bool {
+        true
+    }
+}
+
+pub struct Without<T>(PhantomData<T>);
+
+impl<T> QueryFilter for Without<T>
+where
+    T: Component + 'static,
+{
+    type Fetch<'w> = ();
+
+    fn access(_: &mut Access) {}
+
+    fn matches(archetype: &Archetype) -> bool {
+        !archetype.has_component(ComponentType::of::<T>())
+    }
+
+    fn fetch_init(_: &Archetype, _: SystemTicks) -> Self::Fetch<'_> {}
+
+    unsafe fn filter(_: &Self::Fetch<'_>, _: usize) -> bool {
+        true
+    }
+}
+
+// matches components added since the system last ran
+pub struct Added<T>(PhantomData<T>);
+
+impl<T> QueryFilter for Added<T>
+where
+    T: Component + 'static,
+{
+    type Fetch<'w> = (*const ComponentTicks, SystemTicks);
+
+    fn access(access: &mut Access) {
+        access.read(AccessType::Component(ComponentType::of::<T>()));
+    }
+
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.has_component(ComponentType::of::<T>())
+    }
+
+    fn fetch_init(archetype: &Archetype, ticks: SystemTicks) -> Self::Fetch<'_> {
+        (archetype.column(ComponentType::of::<T>()).unwrap().ticks_ptr(), ticks)
+    }
+
+    unsafe fn filter(fetch: &Self::Fetch<'_>, row: usize) -> bool {
+        (*fetch.0.add(row)).is_added(fetch.1.last_run, fetch.1.change_tick)
+    }
+}
+
+// matches components added or mutably accessed since the system last ran
+pub struct Changed<T>(PhantomData<T>);
+
+impl<T> QueryFilter for Changed<T>
+where
+    T: Component + 'static,
+{
+    type Fetch<'w> = (*const ComponentTicks, SystemTicks);
+
+    fn access(access: &mut Access) {
+        access.read(AccessType::Component(ComponentType::of::<T>()));
+    }
+
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.has_component(ComponentType::of::<T>())
+    }
+
+    fn fetch_init(archetype: &Archetype, ticks: SystemTicks) -> Self::Fetch<'_> {
+        (archetype.column(ComponentType::of::<T>()).unwrap().ticks_ptr(), ticks)
+    }
+
+    unsafe fn filter(fetch: &Self::Fetch<'_>, row: usize) -> bool {
+        (*fetch.0.add(row)).is_changed(fetch.1.last_run, fetch.1.change_tick)
+    }
+}
+
+// matches if any of filters in tuple matches
+pub struct Or<T>(PhantomData<T>);
+
+impl QueryFilter for () {
+    type Fetch<'w> = ();
+
+    fn access(_: &mut Access) {}
+
+    fn matches(_: &Archetype) -> bool {
+        true
+    }
+
+    fn fetch_init(_: &Archetype, _: SystemTicks) -> Self::Fetch<'_> {}
+
+    unsafe fn filter(_: &Self::Fetch<'_>, _: usize) -> bool {
+        true
+    }
+}
+
+macro_rules! impl_query_param {
+    ($($param: ident),*) => {
+        #[allow(non_snake_case)]
+        impl<$($param),*> QueryParam for ($($param,)*)
+        where
+            $($param: QueryParam),*
+        {
+            type Item<'w> = ($($param::Item<'w>,)*);
+            type Fetch<'w> = ($($param::Fetch<'w>,)*);
+
+            fn access(access: &mut Access) {
+                $(
+                    let mut param_access = Access::default();
+                    $param::access(&mut param_access);
+                    access.merge(&param_access, type_name::<Self>());
+                )*
+            }
+
+            fn matches(archetype: &Archetype) -> bool {
+                $($param::matches(archetype))&&*
+            }
+
+            fn fetch_init(archetype: &Archetype, ticks: SystemTicks) -> Self::Fetch<'_> {
+                ($($param::fetch_init(archetype, ticks),)*)
+            }
+
+            unsafe fn fetch<'w>(fetch: &Self::Fetch<'w>, row: usize) -> Self::Item<'w> {
+                let ($($param,)*) = fetch;
+
+                ($($param::fetch($param, row),)*)
+            }
+        }
+
+        impl<$($param),*> ReadOnlyQueryParam for ($($param,)*) where $($param: ReadOnlyQueryParam),* {}
+
+        #[allow(non_snake_case)]
+        impl<$($param),*> QueryFilter for ($($param,)*)
+        where
+            $($param: QueryFilter),*
+        {
+            type Fetch<'w> = ($($param::Fetch<'w>,)*);
+
+            fn access(access: &mut Access) {
+                $($param::access(access);)*
+            }
+
+            fn matches(archetype: &Archetype) -> bool {
+                $($param::matches(archetype))&&*
+            }
+
+            fn fetch_init(archetype: &Archetype, ticks: SystemTicks) -> Self::Fetch<'_> {
+                ($($param::fetch_init(archetype, ticks),)*)
+            }
+
+            unsafe fn filter(fetch: &Self::Fetch<'_>, row: usize) -> bool {
+                let ($($param,)*) = fetch;
+
+                $($param::filter($param, row))&&*
+            }
+        }
+
+        // filters not matching archetype are skipped
+        #[allow(non_snake_case)]
+        impl<$($param),*> QueryFilter for Or<($($param,)*)>
+        where
+            $($param: QueryFilter),*
+        {
+            type Fetch<'w> = ($(Option<$param::Fetch<'w>>,)*);
+
+            fn access(access: &mut Access) {
+                $($param::access(access);)*
+            }
+
+            fn matches(archetype: &Archetype) -> bool {
+                $($param::matches(archetype))||*
+            }
+
+            fn fetch_init(archetype: &Archetype, ticks: SystemTicks) -> Self::Fetch<'_> {
+                ($($param::matches(archetype).then(|| $param::fetch_init(archetype, ticks)),)*)
+            }
+
+            unsafe fn filter(fetch: &Self::Fetch<'_>, row: usize) -> bool {
+                let ($($param,)*) = fetch;
+
+                $($param.as_ref().map(|x| $param::filter(x, row)).unwrap_or(false))||*
+            }
+        }
+    };
+}
+
+macro_rules! impl_query_params {
+    ($first: ident) => {
+        impl_query_param!($first);
+    };
+    ($first: ident, $($rest: ident),*) => {
+        impl_query_param!($first, $($rest),*);
+        impl_query_params!($($rest),*);
+    };
+}
+
+impl_query_params!(P1, P2, P3, P4, P5, P6, P7, P8, P9, P10, P11, P12, P13, P14, P15, P16);
+
+impl<'a, P, F> SystemInput for Query<'a, P, F>
+where
+    P: QueryParam,
+    F: QueryFilter,
+{
+    type ActualInput<'i> = Query<'i, P, F>;
 
     fn is_available(_: &World) -> bool {
         true
     }
 
-    fn new<'w>(world: &'w World, _: Option<&dyn Any>) -> Self::ActualInput<'w> {
+    // filters only read components, so they don't conflict with params of the same query, e.g. `Query<&mut T, Changed<T>>`
+    fn access(access: &mut Access) {
+        P::access(access);
+        F::access(access);
+    }
+
+    fn new<'w>(world: &'w World, ticks: SystemTicks, _: Option<&dyn Any>) -> Self::ActualInput<'w> {
         Self::ActualInput {
             world,
+            ticks,
             _phantom: PhantomData,
         }
     }
@@ -72,6 +462,8 @@ where
 
 #[cfg(test)]
 mod test {
+    use alloc::vec::Vec;
+
     use super::*;
     use crate::ecs::CommandList;
 
@@ -86,11 +478,11 @@ mod test {
         let entity1 = world.spawn().with(TestComponent {}).entity();
         world.spawn().entity();
 
-        let query = Query::<(TestComponent,)>::new(&world);
+        let query = Query::<(Entity, &TestComponent)>::new(&world);
 
         let mut it = query.iter();
 
-        assert!(it.next().unwrap() == entity1);
+        assert!(it.next().unwrap().0 == entity1);
         assert!(it.next().is_none());
     }
 
@@ -105,14 +497,192 @@ mod test {
         let entity1 = world.spawn().with(TestComponent1 {}).with(TestComponent2 {}).entity();
         world.spawn().entity();
 
-        let query = Query::<(TestComponent1, TestComponent2)>::new(&world);
+        let query = Query::<(Entity, &TestComponent1, &TestComponent2)>::new(&world);
 
         let mut it = query.iter();
 
-        assert!(it.next().unwrap() == entity1);
+        assert!(it.next().unwrap().0 == entity1);
         assert!(it.next().is_none());
     }
 
+    #[test]
+    fn test_query_fetch() {
+        struct TestComponent1 {
+            a: u32,
+        }
+        impl Component for TestComponent1 {}
+        struct TestComponent2 {
+            b: u32,
+        }
+        impl Component for TestComponent2 {}
+
+        let mut world = World::new();
+        world.spawn().with(TestComponent1 { a: 1 }).entity();
+        world.spawn().with(TestComponent1 { a: 2 }).with(TestComponent2 { b: 3 }).entity();
+        world.spawn().with(TestComponent2 { b: 4 }).with(TestComponent1 { a: 5 }).entity();
+
+        let mut query = Query::<(&TestComponent1, &mut TestComponent2)>::new_mut(&mut world);
+        for (component1, mut component2) in query.iter_mut() {
+            component2.b += component1.a;
+        }
+
+        let query = Query::<&TestComponent2>::new(&world);
+        let mut values = query.iter().map(|x| x.b).collect::<Vec<_>>();
+        values.sort();
+
+        assert_eq!(values, [5, 9]);
+    }
+
+    #[test]
+    fn test_query_filter() {
+        struct TestComponent1 {}
+        impl Component for TestComponent1 {}
+        struct TestComponent2 {}
+        impl Component for TestComponent2 {}
+        struct TestComponent3 {}
+        impl Component for TestComponent3 {}
+
+        let mut world = World::new();
+        let entity1 = world.spawn().with(TestComponent1 {}).entity();
+        let entity2 = world.spawn().with(TestComponent1 {}).with(TestComponent2 {}).entity();
+        let entity3 = world.spawn().with(TestComponent3 {}).entity();
+
+        let query = Query::<Entity, With<TestComponent2>>::new(&world);
+        assert!(query.iter().collect::<Vec<_>>() == [entity2]);
+
+        let query = Query::<Entity, (With<TestComponent1>, Without<TestComponent2>)>::new(&world);
+        assert!(query.iter().collect::<Vec<_>>() == [entity1]);
+
+        let query = Query::<Entity, Or<(With<TestComponent2>, With<TestComponent3>)>>::new(&world);
+        let mut entities = query.iter().collect::<Vec<_>>();
+        entities.sort();
+        assert!(entities == [entity2, entity3]);
+    }
+
+    #[test]
+    fn test_query_option() {
+        struct TestComponent1 {}
+        impl Component for TestComponent1 {}
+        struct TestComponent2 {
+            a: u32,
+        }
+        impl Component for TestComponent2 {}
+
+        let mut world = World::new();
+        let entity1 = world.spawn().with(TestComponent1 {}).entity();
+        let entity2 = world.spawn().with(TestComponent1 {}).with(TestComponent2 { a: 1 }).entity();
+
+        let query = Query::<(Entity, &TestComponent1, Option<&TestComponent2>)>::new(&world);
+        for (entity, _, component2) in query.iter() {
+            if entity == entity1 {
+                assert!(component2.is_none());
+            } else if entity == entity2 {
+                assert_eq!(component2.unwrap().a, 1);
+            }
+        }
+        assert_eq!(query.iter().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_query_changed() {
+        struct TestComponent {
+            a: u32,
+        }
+        impl Component for TestComponent {}
+        struct TestResult {
+            added: Vec<u32>,
+            changed: Vec<u32>,
+        }
+        impl Component for TestResult {}
+
+        let mut world = World::new();
+        let entity1 = world.spawn().with(TestComponent { a: 1 }).entity();
+        world.spawn().with(TestComponent { a: 2 }).entity();
+
+        world.add_system(
+            |added: Query<&TestComponent, Added<TestComponent>>, changed: Query<&TestComponent, Changed<TestComponent>>| {
+                let added = added.iter().map(|x| x.a).collect();
+                let changed = changed.iter().map(|x| x.a).collect();
+
+                CommandList::new().create_entity((TestResult { added, changed },))
+            },
+        );
+
+        world.update().await;
+        let result = world.components::<TestResult>().last().unwrap().1;
+        assert_eq!(result.added, [1, 2]);
+        assert_eq!(result.changed, [1, 2]);
+
+        world.update().await;
+        let result = world.components::<TestResult>().last().unwrap().1;
+        assert!(result.added.is_empty());
+        assert!(result.changed.is_empty());
+
+        world.component_mut::<TestComponent>(entity1).unwrap().a = 3;
+        world.spawn().with(TestComponent { a: 4 }).entity();
+
+        world.update().await;
+        let result = world.components::<TestResult>().last().unwrap().1;
+        assert_eq!(result.added, [4]);
+        assert_eq!(result.changed, [3, 4]);
+
+        // components are changed only when written
+        let mut query = Query::<&mut TestComponent>::new_mut(&mut world);
+        assert_eq!(query.iter_mut().map(|x| x.a).sum::<u32>(), 9);
+
+        world.update().await;
+        let result = world.components::<TestResult>().last().unwrap().1;
+        assert!(result.changed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_query_mut_changed() {
+        struct TestComponent {
+            a: u32,
+        }
+        impl Component for TestComponent {}
+
+        let mut world = World::new();
+        let entity = world.spawn().with(TestComponent { a: 1 }).entity();
+
+        world.add_system(|mut query: Query<&mut TestComponent, Changed<TestComponent>>| {
+            for mut x in query.iter_mut() {
+                x.a += 1;
+            }
+
+            CommandList::new()
+        });
+
+        world.update().await;
+        assert_eq!(world.component::<TestComponent>(entity).unwrap().a, 2);
+
+        let mut query = Query::<&mut TestComponent, Or<(Added<TestComponent>, Changed<TestComponent>)>>::new_mut(&mut world);
+        assert_eq!(query.iter_mut().count(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_query_new_mut_conflict() {
+        struct TestComponent {}
+        impl Component for TestComponent {}
+
+        let mut world = World::new();
+        world.spawn().with(TestComponent {}).entity();
+
+        Query::<(&mut TestComponent, &mut TestComponent)>::new_mut(&mut world);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_query_conflict() {
+        struct TestComponent {}
+        impl Component for TestComponent {}
+
+        let mut world = World::new();
+
+        world.add_system(|_: Query<(&TestComponent, &mut TestComponent)>| CommandList::new());
+    }
+
     #[tokio::test]
     async fn test_query_system() {
         struct TestComponent {}
@@ -124,10 +694,10 @@ mod test {
         let entity1 = world.spawn().with(TestComponent {}).entity();
         world.spawn().entity();
 
-        world.add_system(move |x: Query<(TestComponent,)>| {
+        world.add_system(move |x: Query<(Entity, &TestComponent)>| {
             let mut it = x.iter();
 
-            assert!(it.next().unwrap() == entity1);
+            assert!(it.next().unwrap().0 == entity1);
             assert!(it.next().is_none());
 
             CommandList::new()
@@ -1,118 +1,191 @@
-use alloc::vec::Vec;
-use core::{mem::size_of, ops::Drop, slice};
+use alloc::{
+    alloc::{alloc, dealloc, handle_alloc_error, realloc, Layout},
+    boxed::Box,
+};
+use core::{cmp::max, mem::size_of, ops::Drop, ptr, ptr::NonNull, slice};
 
 use super::type_descriptor::TypeDescriptor;
 
-// homogeneous vec
+// homogeneous vec. storage is allocated with alignment of the item type, so items can be referenced in place.
 pub struct RawVec {
-    storage: Vec<u8>,
+    storage: NonNull<u8>,
+    len: usize,
+    capacity: usize,
     type_descriptor: TypeDescriptor,
 }
 
+// items are components, which are `Send + Sync`
+unsafe impl Send for RawVec {}
+unsafe impl Sync for RawVec {}
+
 impl RawVec {
+    #[cfg(test)]
     pub fn new<T: 'static>() -> Self {
         Self::with_type_descriptor(TypeDescriptor::new::<T>())
     }
 
     pub(super) fn with_type_descriptor(type_descriptor: TypeDescriptor) -> Self {
+        // zero sized items never allocate
+        let capacity = if type_descriptor.item_size == 0 { usize::MAX } else { 0 };
+
         Self {
-            storage: Vec::new(),
+            storage: NonNull::new(type_descriptor.item_align as *mut u8).unwrap(),
+            len: 0,
+            capacity,
             type_descriptor,
         }
     }
 
-    pub fn insert<T: 'static>(&mut self, index: usize, value: T) {
+    pub fn push<T: 'static>(&mut self, value: T) {
         #[cfg(debug_assertions)]
         assert!(core::any::TypeId::of::<T>() == self.type_descriptor.actual_type);
 
         let value_ptr = &value as *const T as *const u8;
         let value_slice = unsafe { slice::from_raw_parts(value_ptr, size_of::<T>()) };
+
+        self.push_raw(value_slice);
         core::mem::forget(value);
+    }
+
+    pub fn push_raw(&mut self, value_slice: &[u8]) {
+        debug_assert_eq!(value_slice.len(), self.type_descriptor.item_size);
+
+        if self.len == self.capacity {
+            self.grow();
+        }
 
-        self.insert_raw(index, value_slice);
+        unsafe { ptr::copy_nonoverlapping(value_slice.as_ptr(), self.item_ptr(self.len), self.type_descriptor.item_size) };
+        self.len += 1;
     }
 
-    pub fn insert_raw(&mut self, index: usize, value_slice: &[u8]) {
-        let offset = self.get_offset(index);
-        let value_slice = if self.type_descriptor.item_size == 0 { &[0] } else { value_slice };
+    // drops existing value and overwrites it with given one
+    pub fn replace_raw(&mut self, index: usize, value_slice: &[u8]) {
+        if self.type_descriptor.item_size == 0 {
+            return;
+        }
 
-        self.storage.splice(offset..offset, value_slice.iter().cloned());
+        let drop = self.type_descriptor.drop;
+        let item = self.item_mut(index);
+
+        drop(item);
+        item.copy_from_slice(value_slice);
     }
 
     pub fn get<T: 'static>(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+
+    pub fn get_mut<T: 'static>(&mut self, index: usize) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(index)
+    }
+
+    pub fn iter<T: 'static>(&self) -> impl Iterator<Item = &T> {
+        self.as_slice().iter()
+    }
+
+    pub fn iter_mut<T: 'static>(&mut self) -> impl Iterator<Item = &mut T> {
+        self.as_mut_slice().iter_mut()
+    }
+
+    pub fn as_ptr<T: 'static>(&self) -> *const T {
         #[cfg(debug_assertions)]
         assert!(core::any::TypeId::of::<T>() == self.type_descriptor.actual_type);
 
-        let offset = self.get_offset(index);
-        if offset >= self.storage.len() {
-            return None;
-        }
-
-        let value_ptr = &self.storage[offset..offset + size_of::<T>()] as *const [u8] as *const T;
-        Some(unsafe { &*value_ptr })
+        self.storage.as_ptr() as *const T
     }
 
-    pub fn get_mut<T: 'static>(&mut self, index: usize) -> Option<&mut T> {
+    pub fn as_mut_ptr<T: 'static>(&mut self) -> *mut T {
         #[cfg(debug_assertions)]
         assert!(core::any::TypeId::of::<T>() == self.type_descriptor.actual_type);
 
-        let offset = self.get_offset(index);
-        if offset >= self.storage.len() {
-            return None;
+        self.storage.as_ptr() as *mut T
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    // removes item by moving last item into its place. removed item is dropped.
+    pub fn swap_remove(&mut self, index: usize) {
+        if self.type_descriptor.item_size != 0 {
+            (self.type_descriptor.drop)(self.item_mut(index));
         }
 
-        let value_ptr = &mut self.storage[offset..offset + size_of::<T>()] as *mut [u8] as *mut T;
-        Some(unsafe { &mut *value_ptr })
+        self.swap_remove_forget(index);
     }
 
-    pub fn iter<T: 'static>(&self) -> impl Iterator<Item = &T> {
-        #[cfg(debug_assertions)]
-        assert!(core::any::TypeId::of::<T>() == self.type_descriptor.actual_type);
+    // removes item by moving last item into its place. removed item is returned as raw bytes without being dropped.
+    pub fn take_raw(&mut self, index: usize) -> Box<[u8]> {
+        let value = self.item(index).into();
 
-        let chunk_size = if self.type_descriptor.item_size != 0 {
-            self.type_descriptor.item_size
-        } else {
-            1
-        };
+        self.swap_remove_forget(index);
 
-        self.storage.chunks(chunk_size).map(move |x| unsafe { &*(x as *const [u8] as *const T) })
+        value
     }
 
-    pub fn iter_mut<T: 'static>(&mut self) -> impl Iterator<Item = &mut T> {
-        #[cfg(debug_assertions)]
-        assert!(core::any::TypeId::of::<T>() == self.type_descriptor.actual_type);
+    pub fn type_descriptor(&self) -> &TypeDescriptor {
+        &self.type_descriptor
+    }
 
-        let chunk_size = if self.type_descriptor.item_size != 0 {
-            self.type_descriptor.item_size
-        } else {
-            1
-        };
+    // moves item to the end of `other`, filling the hole with last item.
+    pub fn move_to(&mut self, index: usize, other: &mut RawVec) {
+        other.push_raw(self.item(index));
+        self.swap_remove_forget(index);
+    }
 
-        self.storage
-            .chunks_mut(chunk_size)
-            .map(move |x| unsafe { &mut *(x as *mut [u8] as *mut T) })
+    fn swap_remove_forget(&mut self, index: usize) {
+        assert!(index < self.len);
+
+        let last = self.len - 1;
+        if index != last {
+            unsafe { ptr::copy_nonoverlapping(self.item_ptr(last), self.item_ptr(index), self.type_descriptor.item_size) };
+        }
+        self.len = last;
     }
 
-    pub fn remove(&mut self, index: usize) -> bool {
-        let start = self.get_offset(index);
+    fn as_slice<T: 'static>(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
 
-        let end = if self.type_descriptor.item_size != 0 {
-            start + self.type_descriptor.item_size
-        } else {
-            start + 1
-        };
+    fn as_mut_slice<T: 'static>(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
 
-        self.storage.drain(start..end);
+    fn item(&self, index: usize) -> &[u8] {
+        assert!(index < self.len);
 
-        true
+        unsafe { slice::from_raw_parts(self.item_ptr(index), self.type_descriptor.item_size) }
     }
 
-    fn get_offset(&self, index: usize) -> usize {
-        if self.type_descriptor.item_size == 0 {
-            index
-        } else {
-            index * self.type_descriptor.item_size
-        }
+    fn item_mut(&mut self, index: usize) -> &mut [u8] {
+        assert!(index < self.len);
+
+        unsafe { slice::from_raw_parts_mut(self.item_ptr(index), self.type_descriptor.item_size) }
+    }
+
+    // caller should ensure `index` is within capacity
+    unsafe fn item_ptr(&self, index: usize) -> *mut u8 {
+        self.storage.as_ptr().add(index * self.type_descriptor.item_size)
+    }
+
+    fn grow(&mut self) {
+        let capacity = max(self.capacity * 2, 4);
+        let layout = self.layout(capacity);
+
+        let storage = unsafe {
+            if self.capacity == 0 {
+                alloc(layout)
+            } else {
+                realloc(self.storage.as_ptr(), self.layout(self.capacity), layout.size())
+            }
+        };
+
+        self.storage = NonNull::new(storage).unwrap_or_else(|| handle_alloc_error(layout));
+        self.capacity = capacity;
+    }
+
+    fn layout(&self, capacity: usize) -> Layout {
+        Layout::from_size_align(self.type_descriptor.item_size * capacity, self.type_descriptor.item_align).unwrap()
     }
 }
 
@@ -122,9 +195,13 @@ impl Drop for RawVec {
             return;
         }
 
-        self.storage.chunks_mut(self.type_descriptor.item_size).for_each(|x| {
-            (self.type_descriptor.drop)(x);
-        })
+        for index in 0..self.len {
+            (self.type_descriptor.drop)(self.item_mut(index));
+        }
+
+        if self.capacity != 0 {
+            unsafe { dealloc(self.storage.as_ptr(), self.layout(self.capacity)) };
+        }
     }
 }
 
@@ -143,35 +220,11 @@ mod test {
         }
 
         let mut vec = RawVec::new::<TestStruct>();
-        vec.insert(0, TestStruct { a: 1, b: 2 });
-
-        assert_eq!(vec.get::<TestStruct>(0).unwrap().a, 1);
-        assert_eq!(vec.get::<TestStruct>(0).unwrap().b, 2);
-    }
-
-    #[test]
-    fn test_insert() {
-        struct TestStruct {
-            a: usize,
-            b: usize,
-        }
-
-        let mut vec = RawVec::new::<TestStruct>();
-
-        vec.insert(0, TestStruct { a: 1, b: 2 });
-        vec.insert(1, TestStruct { a: 2, b: 3 });
-        vec.insert(2, TestStruct { a: 4, b: 5 });
-
-        vec.insert(1, TestStruct { a: 6, b: 8 });
+        vec.push(TestStruct { a: 1, b: 2 });
 
         assert_eq!(vec.get::<TestStruct>(0).unwrap().a, 1);
         assert_eq!(vec.get::<TestStruct>(0).unwrap().b, 2);
-
-        assert_eq!(vec.get::<TestStruct>(1).unwrap().a, 6);
-        assert_eq!(vec.get::<TestStruct>(1).unwrap().b, 8);
-
-        assert_eq!(vec.get::<TestStruct>(2).unwrap().a, 2);
-        assert_eq!(vec.get::<TestStruct>(2).unwrap().b, 3);
+        assert_eq!(vec.len(), 1);
     }
 
     #[test]
@@ -181,7 +234,7 @@ mod test {
         }
 
         let mut vec = RawVec::new::<TestStruct>();
-        vec.insert(0, TestStruct { test: vec![1, 2, 3, 4] });
+        vec.push(TestStruct { test: vec![1, 2, 3, 4] });
 
         assert_eq!(vec.get::<TestStruct>(0).unwrap().test[0], 1);
         assert_eq!(vec.get::<TestStruct>(0).unwrap().test[1], 2);
@@ -202,8 +255,8 @@ mod test {
         }
         {
             let mut vec = RawVec::new::<TestStruct>();
-            vec.insert(0, TestStruct { dropped: dropped[0].clone() });
-            vec.insert(1, TestStruct { dropped: dropped[1].clone() });
+            vec.push(TestStruct { dropped: dropped[0].clone() });
+            vec.push(TestStruct { dropped: dropped[1].clone() });
         }
 
         assert!(*dropped[0].borrow());
@@ -211,25 +264,83 @@ mod test {
     }
 
     #[test]
-    fn test_remove() {
+    fn test_swap_remove() {
+        let dropped = Rc::new(RefCell::new(false));
+
         struct TestStruct {
             a: usize,
-            b: usize,
+            dropped: Option<Rc<RefCell<bool>>>,
+        }
+
+        impl Drop for TestStruct {
+            fn drop(&mut self) {
+                if let Some(x) = &self.dropped {
+                    *x.borrow_mut() = true;
+                }
+            }
         }
 
         let mut vec = RawVec::new::<TestStruct>();
 
-        vec.insert(0, TestStruct { a: 1, b: 2 });
-        vec.insert(1, TestStruct { a: 2, b: 3 });
-        vec.insert(2, TestStruct { a: 4, b: 5 });
+        vec.push(TestStruct { a: 1, dropped: None });
+        vec.push(TestStruct {
+            a: 2,
+            dropped: Some(dropped.clone()),
+        });
+        vec.push(TestStruct { a: 3, dropped: None });
 
-        vec.remove(1);
+        vec.swap_remove(1);
 
+        assert!(*dropped.borrow());
+        assert_eq!(vec.len(), 2);
         assert_eq!(vec.get::<TestStruct>(0).unwrap().a, 1);
-        assert_eq!(vec.get::<TestStruct>(0).unwrap().b, 2);
+        assert_eq!(vec.get::<TestStruct>(1).unwrap().a, 3);
+    }
+
+    #[test]
+    fn test_move_to() {
+        let mut vec1 = RawVec::new::<Vec<u32>>();
+        let mut vec2 = RawVec::new::<Vec<u32>>();
+
+        vec1.push(vec![1u32]);
+        vec1.push(vec![2u32, 3]);
+
+        vec1.move_to(0, &mut vec2);
+
+        assert_eq!(vec1.len(), 1);
+        assert_eq!(vec1.get::<Vec<u32>>(0).unwrap(), &[2, 3]);
+        assert_eq!(vec2.get::<Vec<u32>>(0).unwrap(), &[1]);
+    }
+
+    #[test]
+    fn test_take_raw() {
+        let mut vec = RawVec::new::<u32>();
+
+        vec.push(1u32);
+        vec.push(2u32);
+
+        let value = vec.take_raw(0);
+
+        assert_eq!(value.len(), 4);
+        assert_eq!(u32::from_ne_bytes(value[..].try_into().unwrap()), 1);
+        assert_eq!(vec.len(), 1);
+        assert_eq!(*vec.get::<u32>(0).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_alignment() {
+        #[repr(align(64))]
+        struct TestStruct {
+            a: u8,
+        }
+
+        let mut vec = RawVec::new::<TestStruct>();
+        for a in 0..10 {
+            vec.push(TestStruct { a });
+        }
 
-        assert_eq!(vec.get::<TestStruct>(1).unwrap().a, 4);
-        assert_eq!(vec.get::<TestStruct>(1).unwrap().b, 5);
+        assert_eq!(vec.as_ptr::<TestStruct>() as usize % 64, 0);
+        assert_eq!(vec.iter::<TestStruct>().map(|x| x.a as u32).sum::<u32>(), 45);
     }
 
     #[test]
@@ -238,13 +349,13 @@ mod test {
 
         let mut vec = RawVec::new::<TestStruct>();
 
-        vec.insert(0, TestStruct {});
-        vec.insert(1, TestStruct {});
-        vec.insert(2, TestStruct {});
+        vec.push(TestStruct {});
+        vec.push(TestStruct {});
+        vec.push(TestStruct {});
 
         assert_eq!(vec.iter::<TestStruct>().count(), 3);
 
-        vec.remove(1);
+        vec.swap_remove(1);
         assert_eq!(vec.iter::<TestStruct>().count(), 2);
     }
 }
@@ -1,22 +1,39 @@
-use core::{any::Any, marker::PhantomData};
+use core::{
+    any::{Any, TypeId},
+    marker::PhantomData,
+};
 
-use super::{system::SystemInput, World};
+use super::{
+    access::{Access, AccessType},
+    system::SystemInput,
+    tick::SystemTicks,
+    World,
+};
 
 pub struct Resource<'a, T: 'static> {
     world: &'a World,
+    ticks: SystemTicks,
     _phantom: PhantomData<T>,
 }
 
-impl<'a, T> SystemInput for Resource<'a, T> {
+impl<'a, T> SystemInput for Resource<'a, T>
+where
+    T: Send + Sync,
+{
     type ActualInput<'i> = Resource<'i, T>;
 
     fn is_available(_: &World) -> bool {
         true
     }
 
-    fn new<'w>(world: &'w World, _: Option<&dyn Any>) -> Self::ActualInput<'w> {
+    fn access(access: &mut Access) {
+        access.read(AccessType::Resource(TypeId::of::<T>()));
+    }
+
+    fn new<'w>(world: &'w World, ticks: SystemTicks, _: Option<&dyn Any>) -> Self::ActualInput<'w> {
         Self::ActualInput::<'w> {
             world,
+            ticks,
             _phantom: PhantomData,
         }
     }
@@ -29,12 +46,67 @@ impl<'a, T> Resource<'a, T> {
     {
         self.world.resource::<T>().unwrap()
     }
+
+    // true if resource was added or mutably accessed since the system last ran
+    pub fn is_changed(&self) -> bool {
+        self.world
+            .resource_ticks::<T>()
+            .unwrap()
+            .is_changed(self.ticks.last_run, self.ticks.change_tick)
+    }
+
+    pub fn is_added(&self) -> bool {
+        self.world
+            .resource_ticks::<T>()
+            .unwrap()
+            .is_added(self.ticks.last_run, self.ticks.change_tick)
+    }
+}
+
+pub struct ResMut<'a, T: 'static> {
+    world: &'a World,
+    ticks: SystemTicks,
+    _phantom: PhantomData<T>,
+}
+
+impl<'a, T> SystemInput for ResMut<'a, T>
+where
+    T: Send + Sync,
+{
+    type ActualInput<'i> = ResMut<'i, T>;
+
+    fn is_available(_: &World) -> bool {
+        true
+    }
+
+    fn access(access: &mut Access) {
+        access.write(AccessType::Resource(TypeId::of::<T>()));
+    }
+
+    fn new<'w>(world: &'w World, ticks: SystemTicks, _: Option<&dyn Any>) -> Self::ActualInput<'w> {
+        Self::ActualInput::<'w> {
+            world,
+            ticks,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> ResMut<'a, T> {
+    pub fn get(&self) -> &T {
+        self.world.resource::<T>().unwrap()
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        // access is validated when system is added, so no other input refers this resource
+        unsafe { self.world.resource_unchecked_mut::<T>(self.ticks.change_tick).unwrap() }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::ecs::CommandList;
+    use crate::ecs::{CommandList, Component};
 
     #[tokio::test]
     async fn test_resource() {
@@ -63,4 +135,56 @@ mod test {
 
         world.update().await;
     }
+
+    #[tokio::test]
+    async fn test_resource_mut() {
+        struct TestResource {
+            a: u32,
+        }
+        let mut world = World::new();
+
+        world.add_resource(TestResource { a: 1 });
+
+        world.add_system(|mut x: ResMut<TestResource>| {
+            x.get_mut().a += 1;
+
+            CommandList::new()
+        });
+
+        world.update().await;
+        world.update().await;
+
+        assert_eq!(world.resource::<TestResource>().unwrap().a, 3);
+    }
+
+    #[tokio::test]
+    async fn test_resource_changed() {
+        struct TestResource {
+            a: u32,
+        }
+        struct TestChangedComponent {
+            changed: bool,
+        }
+        impl Component for TestChangedComponent {}
+
+        let mut world = World::new();
+
+        world.add_resource(TestResource { a: 123 });
+
+        world.add_system(|x: Resource<TestResource>| {
+            let changed = x.is_changed();
+
+            CommandList::new().create_entity((TestChangedComponent { changed },))
+        });
+
+        world.update().await;
+        assert!(world.components::<TestChangedComponent>().last().unwrap().1.changed);
+
+        world.update().await;
+        assert!(!world.components::<TestChangedComponent>().last().unwrap().1.changed);
+
+        world.resource_mut::<TestResource>().unwrap().a = 1;
+        world.update().await;
+        assert!(world.components::<TestChangedComponent>().last().unwrap().1.changed);
+    }
 }
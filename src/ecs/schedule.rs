@@ -0,0 +1,412 @@
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
+
+use super::{
+    system::{IntoSystem, System},
+    World,
+};
+
+// stages run in declaration order. commands issued by systems are applied at the end of each stage.
+// `Startup` and `Shutdown` run once, before the first and after the last frame.
+// `FixedUpdate` is meant to run zero or more times per frame by setting its run count(see `World::set_stage_run_count`),
+// e.g. once for each fixed timestep elapsed by `TimePlugin`. without run count, it runs once per frame like others.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Stage {
+    Startup,
+    PreUpdate,
+    FixedUpdate,
+    Update,
+    PostUpdate,
+    Render,
+    Shutdown,
+}
+
+impl Stage {
+    fn runs_every_frame(&self) -> bool {
+        !matches!(self, Stage::Startup | Stage::Shutdown)
+    }
+}
+
+pub type SystemLabel = &'static str;
+
+type RunCondition = Box<dyn Fn(&World) -> bool>;
+
+pub type RunCount = fn(&mut World) -> u32;
+
+// system with its scheduling information
+pub struct SystemDescriptor {
+    system: Box<dyn System>,
+    labels: Vec<SystemLabel>,
+    before: Vec<SystemLabel>,
+    after: Vec<SystemLabel>,
+    conditions: Vec<RunCondition>,
+}
+
+impl SystemDescriptor {
+    fn new(system: Box<dyn System>) -> Self {
+        Self {
+            system,
+            labels: Vec::new(),
+            before: Vec::new(),
+            after: Vec::new(),
+            conditions: Vec::new(),
+        }
+    }
+
+    pub fn label(mut self, label: SystemLabel) -> Self {
+        self.labels.push(label);
+
+        self
+    }
+
+    pub fn before(mut self, label: SystemLabel) -> Self {
+        self.before.push(label);
+
+        self
+    }
+
+    pub fn after(mut self, label: SystemLabel) -> Self {
+        self.after.push(label);
+
+        self
+    }
+
+    // system runs only if every condition returns true
+    pub fn run_if<F>(mut self, condition: F) -> Self
+    where
+        F: Fn(&World) -> bool + 'static,
+    {
+        self.conditions.push(Box::new(condition));
+
+        self
+    }
+
+    pub(super) fn system(&self) -> &dyn System {
+        &*self.system
+    }
+
+    pub(super) fn system_mut(&mut self) -> &mut (dyn System + 'static) {
+        &mut *self.system
+    }
+
+    pub(super) fn should_run(&self, world: &World) -> bool {
+        self.system.is_available(world) && self.conditions.iter().all(|x| x(world))
+    }
+}
+
+pub trait IntoSystemDescriptor<T>: Sized {
+    fn into_descriptor(self) -> SystemDescriptor;
+
+    fn label(self, label: SystemLabel) -> SystemDescriptor {
+        self.into_descriptor().label(label)
+    }
+
+    fn before(self, label: SystemLabel) -> SystemDescriptor {
+        self.into_descriptor().before(label)
+    }
+
+    fn after(self, label: SystemLabel) -> SystemDescriptor {
+        self.into_descriptor().after(label)
+    }
+
+    fn run_if<F>(self, condition: F) -> SystemDescriptor
+    where
+        F: Fn(&World) -> bool + 'static,
+    {
+        self.into_descriptor().run_if(condition)
+    }
+}
+
+impl<T, P> IntoSystemDescriptor<P> for T
+where
+    T: IntoSystem<P>,
+{
+    fn into_descriptor(self) -> SystemDescriptor {
+        SystemDescriptor::new(self.into_system())
+    }
+}
+
+impl IntoSystemDescriptor<SystemDescriptor> for SystemDescriptor {
+    fn into_descriptor(self) -> SystemDescriptor {
+        self
+    }
+}
+
+#[derive(Default)]
+pub(super) struct StageSystems {
+    systems: Vec<SystemDescriptor>,
+    dependencies: Vec<Vec<usize>>,
+    sorted: bool,
+    run_count: Option<RunCount>,
+}
+
+impl StageSystems {
+    pub(super) fn add(&mut self, system: SystemDescriptor) {
+        self.systems.push(system);
+        self.sorted = false;
+    }
+
+    // adds systems of `other` after existing ones
+    pub(super) fn append(&mut self, other: StageSystems) {
+        for system in other.systems {
+            self.add(system);
+        }
+    }
+
+    // how many times the stage should run this frame, evaluated right before it runs
+    pub(super) fn run_count(&self, world: &mut World) -> u32 {
+        self.run_count.map_or(1, |x| x(world))
+    }
+
+    // returns systems in execution order with indices of systems each one should run after.
+    // ordering is resolved lazily as labels may refer to systems added later.
+    pub(super) fn systems_mut(&mut self) -> (&mut [SystemDescriptor], &[Vec<usize>]) {
+        if !self.sorted {
+            let order = sort_systems(&self.systems);
+
+            let mut systems = self.systems.drain(..).map(Some).collect::<Vec<_>>();
+            self.systems = order.into_iter().map(|x| systems[x].take().unwrap()).collect();
+            self.dependencies = dependencies(&self.systems);
+            self.sorted = true;
+        }
+
+        (&mut self.systems, &self.dependencies)
+    }
+
+    pub(super) fn check_change_ticks(&mut self, change_tick: u32) {
+        for system in &mut self.systems {
+            system.system_mut().check_change_ticks(change_tick);
+        }
+    }
+}
+
+#[derive(Default)]
+pub(super) struct Schedule {
+    stages: BTreeMap<Stage, StageSystems>,
+}
+
+impl Schedule {
+    pub(super) fn add_system(&mut self, stage: Stage, system: SystemDescriptor) {
+        self.stages.entry(stage).or_default().add(system);
+    }
+
+    pub(super) fn set_run_count(&mut self, stage: Stage, run_count: RunCount) {
+        self.stages.entry(stage).or_default().run_count = Some(run_count);
+    }
+
+    // returns stages run on every frame
+    pub(super) fn stages_mut(&mut self) -> impl Iterator<Item = (Stage, &mut StageSystems)> {
+        self.stages
+            .iter_mut()
+            .filter(|(x, _)| x.runs_every_frame())
+            .map(|(x, systems)| (*x, systems))
+    }
+
+    pub(super) fn stage_mut(&mut self, stage: Stage) -> Option<&mut StageSystems> {
+        self.stages.get_mut(&stage)
+    }
+
+    pub(super) fn check_change_ticks(&mut self, change_tick: u32) {
+        for systems in self.stages.values_mut() {
+            systems.check_change_ticks(change_tick);
+        }
+    }
+}
+
+fn dependencies(systems: &[SystemDescriptor]) -> Vec<Vec<usize>> {
+    let has_label = |index: usize, label: &SystemLabel| systems[index].labels.contains(label);
+
+    let mut dependencies = systems.iter().map(|_| Vec::new()).collect::<Vec<_>>();
+    for (index, system) in systems.iter().enumerate() {
+        for label in &system.before {
+            for other in (0..systems.len()).filter(|&x| x != index && has_label(x, label)) {
+                dependencies[other].push(index);
+            }
+        }
+        for label in &system.after {
+            for other in (0..systems.len()).filter(|&x| x != index && has_label(x, label)) {
+                dependencies[index].push(other);
+            }
+        }
+    }
+
+    dependencies
+}
+
+// topological sort of systems by before/after labels, keeping insertion order where unconstrained
+fn sort_systems(systems: &[SystemDescriptor]) -> Vec<usize> {
+    let dependencies = dependencies(systems);
+
+    let mut done = vec![false; systems.len()];
+    let mut order = Vec::with_capacity(systems.len());
+    while order.len() < systems.len() {
+        let next = (0..systems.len())
+            .find(|&x| !done[x] && dependencies[x].iter().all(|&dependency| done[dependency]))
+            .expect("cycle in system ordering");
+
+        done[next] = true;
+        order.push(next);
+    }
+
+    order
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecs::{CommandList, Component, Query, ResMut};
+
+    struct Log {
+        entries: Vec<u32>,
+    }
+
+    #[tokio::test]
+    async fn test_system_order() {
+        let mut world = World::new();
+        world.add_resource(Log { entries: Vec::new() });
+
+        world.add_system(
+            (|mut log: ResMut<Log>| {
+                log.get_mut().entries.push(2);
+
+                CommandList::new()
+            })
+            .label("second")
+            .after("first"),
+        );
+        world.add_system(
+            (|mut log: ResMut<Log>| {
+                log.get_mut().entries.push(3);
+
+                CommandList::new()
+            })
+            .after("second"),
+        );
+        world.add_system(
+            (|mut log: ResMut<Log>| {
+                log.get_mut().entries.push(1);
+
+                CommandList::new()
+            })
+            .label("first"),
+        );
+        world.add_system_to_stage(Stage::PreUpdate, |mut log: ResMut<Log>| {
+            log.get_mut().entries.push(0);
+
+            CommandList::new()
+        });
+
+        world.update().await;
+
+        assert_eq!(world.resource::<Log>().unwrap().entries, [0, 1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_startup_stage() {
+        let mut world = World::new();
+        world.add_resource(Log { entries: Vec::new() });
+
+        world.add_system_to_stage(Stage::Startup, |mut log: ResMut<Log>| {
+            log.get_mut().entries.push(0);
+
+            CommandList::new()
+        });
+        world.add_system(|mut log: ResMut<Log>| {
+            log.get_mut().entries.push(1);
+
+            CommandList::new()
+        });
+        world.add_system_to_stage(Stage::Shutdown, |mut log: ResMut<Log>| {
+            log.get_mut().entries.push(2);
+
+            CommandList::new()
+        });
+
+        world.run_stage(Stage::Startup).await;
+        world.update().await;
+        world.update().await;
+        world.run_stage(Stage::Shutdown).await;
+
+        assert_eq!(world.resource::<Log>().unwrap().entries, [0, 1, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_stage_run_count() {
+        struct Runs(u32);
+
+        let mut world = World::new();
+        world.add_resource(Log { entries: Vec::new() });
+        world.add_resource(Runs(3));
+
+        world.add_system_to_stage(Stage::FixedUpdate, |mut log: ResMut<Log>| {
+            log.get_mut().entries.push(0);
+
+            CommandList::new()
+        });
+        world.set_stage_run_count(Stage::FixedUpdate, |world| {
+            core::mem::replace(&mut world.resource_mut::<Runs>().unwrap().0, 0)
+        });
+
+        world.update().await;
+        assert_eq!(world.resource::<Log>().unwrap().entries.len(), 3);
+
+        world.update().await;
+        assert_eq!(world.resource::<Log>().unwrap().entries.len(), 3);
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn test_system_order_cycle() {
+        let mut world = World::new();
+
+        world.add_system((|_: &World| CommandList::new()).label("a").after("b"));
+        world.add_system((|_: &World| CommandList::new()).label("b").after("a"));
+
+        world.update().await;
+    }
+
+    #[tokio::test]
+    async fn test_stage_commands() {
+        struct TestComponent {
+            a: u32,
+        }
+        impl Component for TestComponent {}
+
+        let mut world = World::new();
+        world.add_resource(Log { entries: Vec::new() });
+
+        world.add_system(|_: &World| CommandList::new().create_entity((TestComponent { a: 1 },)));
+        world.add_system_to_stage(Stage::PostUpdate, |query: Query<&TestComponent>, mut log: ResMut<Log>| {
+            log.get_mut().entries.extend(query.iter().map(|x| x.a));
+
+            CommandList::new()
+        });
+
+        world.update().await;
+
+        assert_eq!(world.resource::<Log>().unwrap().entries, [1]);
+    }
+
+    #[tokio::test]
+    async fn test_run_condition() {
+        struct Enabled {}
+
+        let mut world = World::new();
+        world.add_resource(Log { entries: Vec::new() });
+
+        world.add_system(
+            (|mut log: ResMut<Log>| {
+                log.get_mut().entries.push(1);
+
+                CommandList::new()
+            })
+            .run_if(|world| world.resource::<Enabled>().is_some()),
+        );
+
+        world.update().await;
+        assert!(world.resource::<Log>().unwrap().entries.is_empty());
+
+        world.add_resource(Enabled {});
+        world.update().await;
+        assert_eq!(world.resource::<Log>().unwrap().entries, [1]);
+    }
+}
@@ -0,0 +1,282 @@
+use alloc::vec::Vec;
+use core::{any::type_name, fmt::Debug, mem};
+
+use super::{
+    schedule::{IntoSystemDescriptor, Stage, StageSystems},
+    Component, HierarchyExt, World,
+};
+
+pub trait States: Clone + Eq + Debug + Send + Sync + 'static {}
+
+impl<T> States for T where T: Clone + Eq + Debug + Send + Sync + 'static {}
+
+// current state. changed only by setting `NextState`, at the start of the frame.
+pub struct State<S>(S);
+
+impl<S: States> State<S> {
+    pub fn get(&self) -> &S {
+        &self.0
+    }
+}
+
+// requested transition, applied on the next frame
+pub struct NextState<S>(Option<S>);
+
+impl<S: States> NextState<S> {
+    pub fn set(&mut self, state: S) {
+        self.0 = Some(state);
+    }
+
+    pub fn pending(&self) -> Option<&S> {
+        self.0.as_ref()
+    }
+}
+
+// entity destroyed recursively when exiting given state
+pub struct StateScoped<S>(pub S);
+
+impl<S: States> Component for StateScoped<S> {}
+
+struct StateSchedules<S> {
+    on_enter: Vec<(S, StageSystems)>,
+    on_exit: Vec<(S, StageSystems)>,
+    entered: bool,
+}
+
+type ScheduleSelector<S> = fn(&mut StateSchedules<S>) -> &mut Vec<(S, StageSystems)>;
+
+impl<S: States> StateSchedules<S> {
+    fn systems_mut(schedules: &mut Vec<(S, StageSystems)>, state: S) -> &mut StageSystems {
+        let index = match schedules.iter().position(|x| x.0 == state) {
+            Some(index) => index,
+            None => {
+                schedules.push((state, StageSystems::default()));
+
+                schedules.len() - 1
+            }
+        };
+
+        &mut schedules[index].1
+    }
+
+    fn check_change_ticks(&mut self, change_tick: u32) {
+        for (_, systems) in self.on_enter.iter_mut().chain(self.on_exit.iter_mut()) {
+            systems.check_change_ticks(change_tick);
+        }
+    }
+}
+
+// run condition for `SystemDescriptor::run_if`
+pub fn in_state<S: States>(state: S) -> impl Fn(&World) -> bool {
+    move |world| world.resource::<State<S>>().is_some_and(|x| x.0 == state)
+}
+
+impl World {
+    // adds `State<S>` and `NextState<S>` resources. systems entering `initial` run on first update.
+    // transitions are applied by system labeled with type name of `S`. adding the same state type again does nothing.
+    pub fn add_state<S: States>(&mut self, initial: S) {
+        if self.resource::<State<S>>().is_some() {
+            return;
+        }
+
+        self.add_resource(State(initial));
+        self.add_resource(NextState::<S>(None));
+        self.add_resource(StateSchedules::<S> {
+            on_enter: Vec::new(),
+            on_exit: Vec::new(),
+            entered: false,
+        });
+        self.add_resource_tick_check::<StateSchedules<S>>(|x, tick| x.downcast_mut::<StateSchedules<S>>().unwrap().check_change_ticks(tick));
+
+        self.add_system_to_stage(Stage::PreUpdate, apply_transition::<S>.label(type_name::<S>()));
+    }
+
+    pub fn add_system_on_enter<S, T, P>(&mut self, state: S, system: T)
+    where
+        S: States,
+        T: IntoSystemDescriptor<P>,
+    {
+        let schedules = self.state_schedules::<S>();
+
+        StateSchedules::systems_mut(&mut schedules.on_enter, state).add(system.into_descriptor());
+    }
+
+    pub fn add_system_on_exit<S, T, P>(&mut self, state: S, system: T)
+    where
+        S: States,
+        T: IntoSystemDescriptor<P>,
+    {
+        let schedules = self.state_schedules::<S>();
+
+        StateSchedules::systems_mut(&mut schedules.on_exit, state).add(system.into_descriptor());
+    }
+
+    fn state_schedules<S: States>(&mut self) -> &mut StateSchedules<S> {
+        self.resource_mut::<StateSchedules<S>>()
+            .expect("state type should be added with World::add_state")
+    }
+}
+
+fn apply_transition<S: States>(world: &mut World) {
+    let current = world.resource::<State<S>>().unwrap().0.clone();
+
+    let schedules = world.resource_mut::<StateSchedules<S>>().unwrap();
+    if !schedules.entered {
+        schedules.entered = true;
+        run_state_systems(world, |x| &mut x.on_enter, &current);
+    }
+
+    let next = world.resource_mut::<NextState<S>>().unwrap().0.take();
+    if let Some(next) = next.filter(|x| *x != current) {
+        log::debug!("state transition {:?} -> {:?}", current, next);
+
+        run_state_systems(world, |x| &mut x.on_exit, &current);
+
+        let scoped = world
+            .components::<StateScoped<S>>()
+            .filter(|(_, x)| x.0 == current)
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        for entity in scoped {
+            world.despawn_recursive(entity);
+        }
+
+        world.resource_mut::<State<S>>().unwrap().0 = next.clone();
+        run_state_systems(world, |x| &mut x.on_enter, &next);
+    }
+}
+
+// runs systems one by one in order, as transitions are applied from exclusive system. only systems being run are taken
+// out of the world, so they can add more state systems.
+fn run_state_systems<S: States>(world: &mut World, select: ScheduleSelector<S>, state: &S) {
+    let schedules = select(world.resource_mut::<StateSchedules<S>>().unwrap());
+    let mut systems = match schedules.iter_mut().find(|x| x.0 == *state) {
+        Some(x) => mem::take(&mut x.1),
+        None => return,
+    };
+
+    let mut commands = Vec::new();
+    for descriptor in systems.systems_mut().0 {
+        if !descriptor.should_run(world) {
+            continue;
+        }
+
+        let system = descriptor.system_mut();
+        let result = if system.access().is_exclusive() {
+            system.run_exclusive(world)
+        } else {
+            system.run(world, None)
+        };
+        commands.extend(result.commands);
+    }
+
+    // systems added while running are kept after existing ones
+    let schedules = select(world.resource_mut::<StateSchedules<S>>().unwrap());
+    let added = mem::replace(StateSchedules::systems_mut(schedules, state.clone()), systems);
+    StateSchedules::systems_mut(schedules, state.clone()).append(added);
+
+    world.run_commands(commands);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecs::{CommandList, ResMut, Resource};
+
+    #[derive(Clone, Copy, Eq, PartialEq, Debug)]
+    enum GameState {
+        Menu,
+        InGame,
+    }
+
+    struct Counter {
+        frames: u32,
+        entered: u32,
+    }
+
+    struct MenuComponent {}
+    impl Component for MenuComponent {}
+
+    #[tokio::test]
+    async fn test_state() {
+        let mut world = World::new();
+        world.add_resource(Counter { frames: 0, entered: 0 });
+        world.add_state(GameState::Menu);
+
+        world.add_system_on_enter(GameState::Menu, |_: &World| {
+            CommandList::new().create_entity((MenuComponent {}, StateScoped(GameState::Menu)))
+        });
+        world.add_system_on_enter(GameState::InGame, |mut counter: ResMut<Counter>| {
+            counter.get_mut().entered += 1;
+
+            CommandList::new()
+        });
+        world.add_system(
+            (|mut counter: ResMut<Counter>| {
+                counter.get_mut().frames += 1;
+
+                CommandList::new()
+            })
+            .run_if(in_state(GameState::InGame)),
+        );
+
+        world.update().await;
+        assert_eq!(world.components::<MenuComponent>().count(), 1);
+        assert_eq!(world.resource::<Counter>().unwrap().frames, 0);
+
+        world.add_system(|state: Resource<State<GameState>>, mut next: ResMut<NextState<GameState>>| {
+            if *state.get().get() == GameState::Menu {
+                next.get_mut().set(GameState::InGame);
+            }
+
+            CommandList::new()
+        });
+
+        // transition is requested on this frame and applied on next one
+        world.update().await;
+        assert_eq!(*world.resource::<State<GameState>>().unwrap().get(), GameState::Menu);
+
+        world.update().await;
+        assert_eq!(*world.resource::<State<GameState>>().unwrap().get(), GameState::InGame);
+        assert_eq!(world.components::<MenuComponent>().count(), 0);
+
+        let counter = world.resource::<Counter>().unwrap();
+        assert_eq!((counter.frames, counter.entered), (1, 1));
+    }
+
+    #[derive(Clone, Copy, Eq, PartialEq, Debug)]
+    enum MusicState {
+        Playing,
+    }
+
+    #[tokio::test]
+    async fn test_state_add_systems() {
+        let mut world = World::new();
+        world.add_resource(Counter { frames: 0, entered: 0 });
+        world.add_state(GameState::Menu);
+        world.add_state(GameState::InGame);
+        world.add_state(MusicState::Playing);
+
+        world.add_system_on_enter(GameState::Menu, |world: &mut World| {
+            world.add_system_on_enter(GameState::InGame, |mut counter: ResMut<Counter>| {
+                counter.get_mut().entered += 1;
+
+                CommandList::new()
+            });
+            world.resource_mut::<NextState<GameState>>().unwrap().set(GameState::InGame);
+        });
+        world.add_system_on_enter(MusicState::Playing, |mut counter: ResMut<Counter>| {
+            counter.get_mut().frames += 1;
+
+            CommandList::new()
+        });
+
+        // transition requested while entering initial state is applied right after it
+        world.update().await;
+        assert_eq!(*world.resource::<State<GameState>>().unwrap().get(), GameState::InGame);
+
+        world.update().await;
+        let counter = world.resource::<Counter>().unwrap();
+        assert_eq!((counter.frames, counter.entered), (1, 1));
+    }
+}
@@ -1,12 +1,20 @@
-use core::{any::Any, marker::PhantomData};
+use core::{
+    any::{type_name, Any},
+    marker::PhantomData,
+};
 
-use super::{CommandList, World};
+use super::{
+    access::Access,
+    tick::{check_tick, SystemTicks},
+    CommandList, World,
+};
 
 pub trait SystemInput {
     type ActualInput<'i>: SystemInput;
 
     fn is_available(world: &World) -> bool;
-    fn new<'w>(world: &'w World, extra: Option<&dyn Any>) -> Self::ActualInput<'w>;
+    fn access(access: &mut Access);
+    fn new<'w>(world: &'w World, ticks: SystemTicks, extra: Option<&dyn Any>) -> Self::ActualInput<'w>;
 }
 
 impl<'a> SystemInput for &'a World {
@@ -16,7 +24,11 @@ impl<'a> SystemInput for &'a World {
         true
     }
 
-    fn new<'w>(world: &'w World, _: Option<&dyn Any>) -> Self::ActualInput<'w> {
+    fn access(access: &mut Access) {
+        access.read_all();
+    }
+
+    fn new<'w>(world: &'w World, _: SystemTicks, _: Option<&dyn Any>) -> Self::ActualInput<'w> {
         world
     }
 }
@@ -27,50 +39,52 @@ impl SystemInput for u32 {
         true
     }
 
-    fn new<'w>(_: &'w World, extra: Option<&dyn Any>) -> Self::ActualInput<'w> {
+    fn access(_: &mut Access) {}
+
+    fn new<'w>(_: &'w World, _: SystemTicks, extra: Option<&dyn Any>) -> Self::ActualInput<'w> {
         *extra.unwrap().downcast_ref::<Self>().unwrap()
     }
 }
 
-pub trait System {
+pub trait System: Send {
+    fn access(&self) -> &Access;
     fn is_available(&self, world: &World) -> bool;
-    fn run(&self, world: &World, extra: Option<&dyn Any>) -> CommandList;
-}
-
-struct SystemFunction<F, Input>(F, PhantomData<Input>);
+    fn run(&mut self, world: &World, extra: Option<&dyn Any>) -> CommandList;
 
-impl<F, Input> SystemFunction<F, Input> {
-    pub fn new(f: F) -> Self {
-        Self(f, PhantomData)
+    // called instead of `run` if system has exclusive access
+    fn run_exclusive(&mut self, world: &mut World) -> CommandList {
+        self.run(world, None)
     }
-}
 
-impl<Func, Input1> System for SystemFunction<Func, (Input1,)>
-where
-    Func: Fn(Input1::ActualInput<'_>) -> CommandList,
-    Input1: SystemInput,
-{
-    fn is_available(&self, world: &World) -> bool {
-        Input1::is_available(world)
-    }
+    // clamps stored ticks, see `World::check_change_ticks`
+    fn check_change_ticks(&mut self, _change_tick: u32) {}
+}
 
-    fn run(&self, world: &World, extra: Option<&dyn Any>) -> CommandList {
-        (self.0)(Input1::new(world, extra))
-    }
+struct SystemFunction<F, Input> {
+    f: F,
+    access: Access,
+    last_run: u32,
+    _phantom: PhantomData<fn() -> Input>,
 }
 
-impl<Func, Input1, Input2> System for SystemFunction<Func, (Input1, Input2)>
-where
-    Func: Fn(Input1::ActualInput<'_>, Input2::ActualInput<'_>) -> CommandList,
-    Input1: SystemInput,
-    Input2: SystemInput,
-{
-    fn is_available(&self, world: &World) -> bool {
-        Input1::is_available(world) && Input2::is_available(world)
+impl<F, Input> SystemFunction<F, Input> {
+    pub fn new(f: F, access: Access) -> Self {
+        Self {
+            f,
+            access,
+            last_run: 0,
+            _phantom: PhantomData,
+        }
     }
 
-    fn run(&self, world: &World, extra: Option<&dyn Any>) -> CommandList {
-        (self.0)(Input1::new(world, extra), Input2::new(world, extra))
+    fn ticks(&mut self, world: &World) -> SystemTicks {
+        let ticks = SystemTicks {
+            last_run: self.last_run,
+            change_tick: world.increment_change_tick(),
+        };
+        self.last_run = ticks.change_tick;
+
+        ticks
     }
 }
 
@@ -78,31 +92,133 @@ pub trait IntoSystem<T> {
     fn into_system(self) -> Box<dyn System>;
 }
 
-impl<Func, Input1> IntoSystem<(Input1,)> for Func
+macro_rules! impl_system_function {
+    ($($input: ident),*) => {
+        impl<Func, $($input),*> System for SystemFunction<Func, ($($input,)*)>
+        where
+            Func: Fn($($input::ActualInput<'_>),*) -> CommandList + Send,
+            $($input: SystemInput),*
+        {
+            fn access(&self) -> &Access {
+                &self.access
+            }
+
+            fn is_available(&self, world: &World) -> bool {
+                $($input::is_available(world))&&*
+            }
+
+            fn run(&mut self, world: &World, extra: Option<&dyn Any>) -> CommandList {
+                let ticks = self.ticks(world);
+
+                (self.f)($($input::new(world, ticks, extra)),*)
+            }
+
+            fn check_change_ticks(&mut self, change_tick: u32) {
+                check_tick(&mut self.last_run, change_tick);
+            }
+        }
+
+        impl<Func, $($input),*> IntoSystem<($($input,)*)> for Func
+        where
+            Func: Fn($($input),*) -> CommandList + Fn($($input::ActualInput<'_>),*) -> CommandList + Send + 'static,
+            $($input: SystemInput + 'static),*
+        {
+            fn into_system(self) -> Box<dyn System> {
+                let mut access = Access::default();
+                $(access.merge(&input_access::<$input>(), type_name::<Func>());)*
+
+                Box::new(SystemFunction::new(self, access))
+            }
+        }
+
+        // nested tuple of inputs is a single input
+        impl<$($input),*> SystemInput for ($($input,)*)
+        where
+            $($input: SystemInput),*
+        {
+            type ActualInput<'i> = ($($input::ActualInput<'i>,)*);
+
+            fn is_available(world: &World) -> bool {
+                $($input::is_available(world))&&*
+            }
+
+            fn access(access: &mut Access) {
+                $(access.merge(&input_access::<$input>(), type_name::<Self>());)*
+            }
+
+            fn new<'w>(world: &'w World, ticks: SystemTicks, extra: Option<&dyn Any>) -> Self::ActualInput<'w> {
+                ($($input::new(world, ticks, extra),)*)
+            }
+        }
+    };
+}
+
+macro_rules! impl_system_functions {
+    ($first: ident) => {
+        impl_system_function!($first);
+    };
+    ($first: ident, $($rest: ident),*) => {
+        impl_system_function!($first, $($rest),*);
+        impl_system_functions!($($rest),*);
+    };
+}
+
+impl_system_functions!(
+    Input1, Input2, Input3, Input4, Input5, Input6, Input7, Input8, Input9, Input10, Input11, Input12, Input13, Input14, Input15, Input16
+);
+
+// systems taking `&mut World`. they run alone, so no other system runs concurrently.
+struct ExclusiveSystem<F> {
+    f: F,
+    access: Access,
+}
+
+impl<F> System for ExclusiveSystem<F>
 where
-    Func: Fn(Input1) -> CommandList + Fn(Input1::ActualInput<'_>) -> CommandList + 'static,
-    Input1: SystemInput + 'static,
+    F: FnMut(&mut World) + Send,
 {
-    fn into_system(self) -> Box<dyn System> {
-        Box::new(SystemFunction::new(self))
+    fn access(&self) -> &Access {
+        &self.access
+    }
+
+    fn is_available(&self, _: &World) -> bool {
+        true
+    }
+
+    fn run(&mut self, _: &World, _: Option<&dyn Any>) -> CommandList {
+        unreachable!("exclusive system should be run by run_exclusive")
+    }
+
+    fn run_exclusive(&mut self, world: &mut World) -> CommandList {
+        (self.f)(world);
+
+        CommandList::new()
     }
 }
 
-impl<Func, Input1, Input2> IntoSystem<(Input1, Input2)> for Func
+impl<Func> IntoSystem<fn(&mut World)> for Func
 where
-    Func: Fn(Input1, Input2) -> CommandList + Fn(Input1::ActualInput<'_>, Input2::ActualInput<'_>) -> CommandList + 'static,
-    Input1: SystemInput + 'static,
-    Input2: SystemInput + 'static,
+    Func: FnMut(&mut World) + Send + 'static,
 {
     fn into_system(self) -> Box<dyn System> {
-        Box::new(SystemFunction::new(self))
+        let mut access = Access::default();
+        access.exclusive();
+
+        Box::new(ExclusiveSystem { f: self, access })
     }
 }
 
+fn input_access<T: SystemInput>() -> Access {
+    let mut access = Access::default();
+    T::access(&mut access);
+
+    access
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::ecs::{CommandList, Component};
+    use crate::ecs::{CommandList, Component, Entity, Query, ResMut, Resource};
 
     #[tokio::test]
     async fn test_system() {
@@ -125,4 +241,75 @@ mod test {
         assert_eq!(world.component::<TestComponent1>(entity).unwrap().a, 2);
         assert_eq!(world.component::<TestComponent2>(entity).unwrap().a, 3);
     }
+
+    #[tokio::test]
+    async fn test_exclusive_system() {
+        struct TestComponent {
+            a: u32,
+        }
+        impl Component for TestComponent {}
+
+        let mut world = World::new();
+        let entity = world.spawn().with(TestComponent { a: 2 }).entity();
+
+        world.add_system(move |world: &mut World| {
+            world.component_mut::<TestComponent>(entity).unwrap().a += 1;
+        });
+
+        world.update().await;
+
+        assert_eq!(world.component::<TestComponent>(entity).unwrap().a, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_conflicting_system() {
+        struct TestComponent {}
+        impl Component for TestComponent {}
+
+        let mut world = World::new();
+
+        world.add_system(|_: &World, _: Query<&mut TestComponent>| CommandList::new());
+    }
+
+    #[tokio::test]
+    async fn test_system_many_inputs() {
+        struct TestComponent {
+            a: u32,
+        }
+        impl Component for TestComponent {}
+
+        struct TestResource1 {
+            a: u32,
+        }
+        struct TestResource2 {
+            a: u32,
+        }
+        struct TestResource3 {
+            a: u32,
+        }
+
+        let mut world = World::new();
+        world.add_resource(TestResource1 { a: 1 });
+        world.add_resource(TestResource2 { a: 2 });
+        world.add_resource(TestResource3 { a: 0 });
+        world.spawn().with(TestComponent { a: 3 }).entity();
+
+        world.add_system(
+            |query: Query<&TestComponent>,
+             (resource1, (resource2,)): (Resource<TestResource1>, (Resource<TestResource2>,)),
+             mut resource3: ResMut<TestResource3>,
+             entities: Query<Entity>| {
+                assert_eq!(entities.iter().count(), 1);
+
+                resource3.get_mut().a = query.iter().map(|x| x.a).sum::<u32>() + resource1.get().a + resource2.get().a;
+
+                CommandList::new()
+            },
+        );
+
+        world.update().await;
+
+        assert_eq!(world.resource::<TestResource3>().unwrap().a, 6);
+    }
 }
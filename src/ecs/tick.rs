@@ -0,0 +1,131 @@
+use core::ops::{Deref, DerefMut};
+
+// world increments change tick every time a system runs. tick 0 is never used so it can be used as `never ran`.
+pub(super) const INITIAL_TICK: u32 = 1;
+
+// world checks stored ticks whenever change tick advances this much, so no tick gets older than `MAX_CHANGE_AGE`
+// plus this much before it's clamped.
+pub(super) const CHECK_TICK_THRESHOLD: u32 = 518_400_000;
+
+// ticks older than this relative to current tick are clamped, so they aren't mistaken as new after change tick wraps
+pub(super) const MAX_CHANGE_AGE: u32 = u32::MAX - (2 * CHECK_TICK_THRESHOLD - 1);
+
+// ticks passed to system inputs. changes made after `last_run` are considered new.
+#[derive(Clone, Copy)]
+pub struct SystemTicks {
+    pub last_run: u32,
+    pub change_tick: u32,
+}
+
+#[derive(Clone, Copy)]
+pub struct ComponentTicks {
+    pub added: u32,
+    pub changed: u32,
+}
+
+impl ComponentTicks {
+    pub fn new(tick: u32) -> Self {
+        Self { added: tick, changed: tick }
+    }
+
+    pub fn is_added(&self, last_run: u32, current: u32) -> bool {
+        is_newer(self.added, last_run, current)
+    }
+
+    pub fn is_changed(&self, last_run: u32, current: u32) -> bool {
+        is_newer(self.changed, last_run, current)
+    }
+
+    pub fn set_changed(&mut self, tick: u32) {
+        self.changed = tick;
+    }
+
+    pub(super) fn check_ticks(&mut self, change_tick: u32) {
+        check_tick(&mut self.added, change_tick);
+        check_tick(&mut self.changed, change_tick);
+    }
+}
+
+// mutable reference to a component, marking it as changed only when actually mutably dereferenced
+pub struct Mut<'a, T> {
+    value: &'a mut T,
+    ticks: &'a mut ComponentTicks,
+    change_tick: u32,
+}
+
+impl<'a, T> Mut<'a, T> {
+    pub(super) fn new(value: &'a mut T, ticks: &'a mut ComponentTicks, change_tick: u32) -> Self {
+        Self { value, ticks, change_tick }
+    }
+
+    // marks component as changed and returns underlying reference
+    pub fn into_inner(self) -> &'a mut T {
+        self.ticks.set_changed(self.change_tick);
+
+        self.value
+    }
+}
+
+impl<T> Deref for Mut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T> DerefMut for Mut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.ticks.set_changed(self.change_tick);
+
+        self.value
+    }
+}
+
+// compares relative to current tick to handle wraparound
+fn is_newer(tick: u32, last_run: u32, current: u32) -> bool {
+    current.wrapping_sub(tick) < current.wrapping_sub(last_run)
+}
+
+pub(super) fn check_tick(tick: &mut u32, change_tick: u32) {
+    if change_tick.wrapping_sub(*tick) > MAX_CHANGE_AGE {
+        *tick = change_tick.wrapping_sub(MAX_CHANGE_AGE);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ComponentTicks, MAX_CHANGE_AGE};
+
+    #[test]
+    fn test_ticks() {
+        let mut ticks = ComponentTicks::new(3);
+
+        assert!(ticks.is_added(2, 4));
+        assert!(!ticks.is_added(3, 4));
+
+        ticks.set_changed(5);
+        assert!(ticks.is_changed(4, 6));
+        assert!(!ticks.is_added(4, 6));
+    }
+
+    #[test]
+    fn test_ticks_wraparound() {
+        let ticks = ComponentTicks::new(1);
+
+        assert!(ticks.is_changed(u32::MAX - 1, 2));
+        assert!(!ticks.is_changed(1, 2));
+    }
+
+    #[test]
+    fn test_check_ticks() {
+        let mut ticks = ComponentTicks::new(1);
+        ticks.set_changed(3);
+
+        ticks.check_ticks(MAX_CHANGE_AGE + 2);
+        assert_eq!(ticks.added, 2);
+        assert_eq!(ticks.changed, 3);
+
+        assert!(!ticks.is_added(MAX_CHANGE_AGE, MAX_CHANGE_AGE + 2));
+    }
+}
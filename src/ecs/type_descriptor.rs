@@ -1,15 +1,17 @@
-use alloc::boxed::Box;
 use core::{
     any::TypeId,
     mem::{align_of, size_of},
+    ptr,
 };
 
 use crate::utils::round_up;
 
-type DropFn = Box<dyn Fn(&mut [u8])>;
+type DropFn = fn(&mut [u8]);
 
+#[derive(Clone)]
 pub struct TypeDescriptor {
     pub(super) item_size: usize,
+    pub(super) item_align: usize,
     pub(super) drop: DropFn,
 
     #[cfg(debug_assertions)]
@@ -20,28 +22,27 @@ impl TypeDescriptor {
     pub fn new<T: 'static>() -> Self {
         Self {
             item_size: round_up(size_of::<T>(), align_of::<T>()),
-            drop: Box::new(Self::drop::<T>),
+            item_align: align_of::<T>(),
+            drop: Self::drop::<T>,
 
             #[cfg(debug_assertions)]
             actual_type: TypeId::of::<T>(),
         }
     }
 
-    pub fn from_raw<F>(size: usize, align: usize, drop: F, _type_id: TypeId) -> Self
-    where
-        for<'a> F: Fn(&'a mut [u8]) + 'static,
-    {
+    pub fn from_raw(size: usize, align: usize, drop: DropFn, _type_id: TypeId) -> Self {
         Self {
             item_size: round_up(size, align),
-            drop: Box::new(drop),
+            item_align: align,
+            drop,
 
             #[cfg(debug_assertions)]
             actual_type: _type_id,
         }
     }
 
+    // byte buffers, e.g. removed components, aren't aligned for `T`, so value is read out before dropping
     fn drop<T: 'static>(data: &mut [u8]) {
-        let value_ptr = data.as_mut_ptr() as *mut T;
-        unsafe { value_ptr.drop_in_place() }
+        drop(unsafe { ptr::read_unaligned(data.as_ptr() as *const T) });
     }
 }
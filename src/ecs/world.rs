@@ -1,19 +1,27 @@
-use alloc::{boxed::Box, vec::Vec};
+use alloc::{boxed::Box, vec, vec::Vec};
 use core::{
     any::{Any, TypeId},
+    cell::UnsafeCell,
     future::Future,
+    iter,
+    sync::atomic::{AtomicIsize, AtomicU32, Ordering},
 };
 
 use futures::{future::BoxFuture, poll, task::Poll, FutureExt};
 use hashbrown::HashMap;
 
 use super::{
+    archetype::{Archetype, ArchetypeId, EntityLocation},
     builder::EntityBuilder,
     bundle::ComponentBundle,
     command::{Command, CommandList},
     component::ComponentContainer,
-    sparse_raw_vec::SparseRawVec,
+    event::Events,
+    executor, hierarchy,
+    schedule::{IntoSystemDescriptor, RunCount, Schedule, Stage},
     system::{IntoSystem, System, SystemInput},
+    tick::{ComponentTicks, Mut, CHECK_TICK_THRESHOLD, INITIAL_TICK},
+    type_descriptor::TypeDescriptor,
     Component, Entity,
 };
 
@@ -23,6 +31,13 @@ pub type EventType = TypeId;
 
 type PendingFuture = BoxFuture<'static, Box<dyn Any>>;
 
+const EMPTY_ARCHETYPE: ArchetypeId = 0;
+
+struct EntityMeta {
+    generation: u32,
+    location: Option<EntityLocation>,
+}
+
 pub trait AsyncSingleArgFnOnce<Arg>: FnOnce(Arg) -> <Self as AsyncSingleArgFnOnce<Arg>>::Fut {
     type Fut: Future<Output = <Self as AsyncSingleArgFnOnce<Arg>>::Output>;
     type Output;
@@ -37,24 +52,46 @@ where
     type Output = Fut::Output;
 }
 
+// resources are mutated through shared world reference by `ResMut`, relying on system access validation
+type ResourceCell = UnsafeCell<(Box<dyn Any>, ComponentTicks)>;
+
 pub struct World {
-    components: HashMap<ComponentType, SparseRawVec<Entity>>,
-    resources: HashMap<ResourceType, Box<dyn Any>>,
-    entities: u32,
+    archetypes: Vec<Archetype>,
+    archetype_ids: HashMap<Vec<ComponentType>, ArchetypeId>,
+    type_descriptors: HashMap<ComponentType, TypeDescriptor>,
+    entities: Vec<EntityMeta>,
+    free_entities: Vec<u32>,
+    // entities are reserved from `free_entities` while cursor is positive, and as new ids after it goes negative
+    free_cursor: AtomicIsize,
+    resources: HashMap<ResourceType, ResourceCell>,
+    change_tick: AtomicU32,
+    last_check_tick: u32,
+    // clamps ticks stored inside resources, e.g. events and state systems
+    resource_tick_checks: HashMap<ResourceType, fn(&mut dyn Any, u32)>,
     pending: Vec<(PendingFuture, Box<dyn System>)>,
-    events: HashMap<EventType, Box<dyn Any>>,
-    systems: Vec<Box<dyn System>>,
+    events: HashMap<EventType, fn(&mut World)>,
+    schedule: Schedule,
 }
 
 impl World {
     pub fn new() -> Self {
+        let mut archetype_ids = HashMap::new();
+        archetype_ids.insert(Vec::new(), EMPTY_ARCHETYPE);
+
         Self {
-            components: HashMap::new(),
+            archetypes: vec![Archetype::new(Vec::new(), iter::empty())],
+            archetype_ids,
+            type_descriptors: HashMap::new(),
+            entities: Vec::new(),
+            free_entities: Vec::new(),
+            free_cursor: AtomicIsize::new(0),
             resources: HashMap::new(),
-            entities: 0,
+            change_tick: AtomicU32::new(INITIAL_TICK),
+            last_check_tick: INITIAL_TICK,
+            resource_tick_checks: HashMap::new(),
             pending: Vec::new(),
             events: HashMap::new(),
-            systems: Vec::new(),
+            schedule: Schedule::default(),
         }
     }
 
@@ -68,23 +105,101 @@ impl World {
     }
 
     pub fn spawn(&mut self) -> EntityBuilder<'_> {
-        let id = self.entities;
+        self.flush_entities();
+
+        let entity = self.reserve_entity();
+        self.flush_entities();
+
+        EntityBuilder::new(self, entity)
+    }
+
+    // reserves entity id without structural change. reserved entity becomes alive without components when commands are
+    // applied or on next spawn.
+    pub fn reserve_entity(&self) -> Entity {
+        let cursor = self.free_cursor.fetch_sub(1, Ordering::Relaxed);
+
+        if cursor > 0 {
+            let id = self.free_entities[cursor as usize - 1];
+
+            Entity {
+                id,
+                generation: self.entities[id as usize].generation,
+            }
+        } else {
+            Entity {
+                id: (self.entities.len() as isize - cursor) as u32,
+                generation: 0,
+            }
+        }
+    }
+
+    // makes reserved entities alive
+    fn flush_entities(&mut self) {
+        let cursor = *self.free_cursor.get_mut();
+        if cursor == self.free_entities.len() as isize {
+            return;
+        }
 
-        self.entities += 1;
+        let reused = self.free_entities.split_off(cursor.max(0) as usize);
+        for id in reused.into_iter().rev() {
+            let generation = self.entities[id as usize].generation;
 
-        EntityBuilder::new(self, Entity { id })
+            self.push_empty_entity(Entity { id, generation });
+        }
+
+        for _ in 0..-cursor.min(0) {
+            self.entities.push(EntityMeta {
+                generation: 0,
+                location: None,
+            });
+
+            self.push_empty_entity(Entity {
+                id: self.entities.len() as u32 - 1,
+                generation: 0,
+            });
+        }
+
+        *self.free_cursor.get_mut() = self.free_entities.len() as isize;
+    }
+
+    fn push_empty_entity(&mut self, entity: Entity) {
+        let row = self.archetypes[EMPTY_ARCHETYPE].push_entity(entity);
+
+        self.entities[entity.id as usize].location = Some(EntityLocation {
+            archetype: EMPTY_ARCHETYPE,
+            row,
+        });
     }
 
     pub fn destroy(&mut self, entity: Entity) {
-        for (_, storage) in self.components.iter_mut() {
-            storage.remove(entity);
+        self.flush_entities();
+        hierarchy::detach(self, entity);
+
+        let location = if let Some(x) = self.location(entity) {
+            x
+        } else {
+            return;
+        };
+
+        let meta = &mut self.entities[entity.id as usize];
+        meta.location = None;
+        meta.generation = meta.generation.wrapping_add(1);
+        self.free_entities.push(entity.id);
+        *self.free_cursor.get_mut() = self.free_entities.len() as isize;
+
+        if let Some(moved) = self.archetypes[location.archetype].remove(location.row) {
+            self.entities[moved.id as usize].location = Some(location);
         }
     }
 
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.location(entity).is_some()
+    }
+
     pub fn spawn_bundle<T: 'static + ComponentBundle>(&mut self, bundle: T) -> Entity {
         let entity = self.spawn().entity();
 
-        self.add_bundle(entity, bundle);
+        self.add_components_raw(entity, bundle.to_component_containers());
 
         entity
     }
@@ -95,61 +210,157 @@ impl World {
 
     pub fn add_component<T: 'static + Component>(&mut self, entity: Entity, component: T) {
         let component_type = Self::get_component_type::<T>();
+        let location = match self.alive_location(entity) {
+            Some(x) => x,
+            None => return,
+        };
 
-        let vec = if let Some(x) = self.components.get_mut(&component_type) {
-            x
-        } else {
-            let vec = SparseRawVec::new::<T>();
-            self.components.insert(component_type, vec);
+        let tick = *self.change_tick.get_mut();
+
+        if let Some(x) = self.archetypes[location.archetype].column_mut(component_type) {
+            *x.get_mut::<T>(location.row, tick).unwrap() = component;
+
+            return;
+        }
+
+        self.type_descriptors.entry(component_type).or_insert_with(TypeDescriptor::new::<T>);
 
-            self.components.get_mut(&component_type).unwrap()
+        let target = self.archetype_with(location.archetype, component_type);
+        self.move_entity(entity, location, target);
+
+        self.archetypes[target].column_mut(component_type).unwrap().push(component, tick);
+    }
+
+    pub(super) fn add_components_raw(&mut self, entity: Entity, component_containers: Vec<ComponentContainer>) {
+        let location = match self.alive_location(entity) {
+            Some(x) => x,
+            None => return,
         };
+        let tick = *self.change_tick.get_mut();
+
+        let mut new_components = Vec::with_capacity(component_containers.len());
+        for mut component_container in component_containers {
+            if let Some(x) = self.archetypes[location.archetype].column_mut(component_container.component_type) {
+                x.replace_raw(location.row, &component_container.take_data(), tick);
+            } else {
+                new_components.push(component_container);
+            }
+        }
+
+        if new_components.is_empty() {
+            return;
+        }
+
+        let mut component_types = self.archetypes[location.archetype].component_types().to_vec();
+        for component_container in &new_components {
+            self.type_descriptors
+                .entry(component_container.component_type)
+                .or_insert_with(|| component_container.type_descriptor.clone());
+
+            component_types.push(component_container.component_type);
+        }
+        component_types.sort();
+        component_types.dedup();
+
+        let target = self.find_or_create_archetype(component_types);
+        let (location, _) = self.move_entity(entity, location, target);
 
-        vec.insert(entity, component);
+        for mut component_container in new_components {
+            let column = self.archetypes[target].column_mut(component_container.component_type).unwrap();
+
+            // same component type may appear more than once
+            let data = component_container.take_data();
+            if column.len() > location.row {
+                column.replace_raw(location.row, &data, tick);
+            } else {
+                column.push_raw(&data, tick);
+            }
+        }
     }
 
-    fn add_component_raw(&mut self, entity: Entity, component_container: ComponentContainer) {
-        let vec = if let Some(x) = self.components.get_mut(&component_container.component_type) {
+    pub fn remove_component<T: 'static + Component>(&mut self, entity: Entity) -> Option<T> {
+        let component_type = Self::get_component_type::<T>();
+
+        self.remove_components_raw(entity, &[component_type]).pop().map(|x| x.into_inner())
+    }
+
+    // removes components the entity has among `component_types` without dropping them
+    pub(super) fn remove_components_raw(&mut self, entity: Entity, component_types: &[ComponentType]) -> Vec<ComponentContainer> {
+        let location = if let Some(x) = self.location(entity) {
             x
         } else {
-            let vec = SparseRawVec::with_type_descriptor(component_container.type_descriptor);
-            self.components.insert(component_container.component_type, vec);
+            return Vec::new();
+        };
+
+        let source = &self.archetypes[location.archetype];
+        let target = if let [component_type] = component_types {
+            if !source.has_component(*component_type) {
+                return Vec::new();
+            }
+
+            self.archetype_without(location.archetype, *component_type)
+        } else {
+            let remaining = source
+                .component_types()
+                .iter()
+                .filter(|x| !component_types.contains(x))
+                .cloned()
+                .collect::<Vec<_>>();
+            if remaining.len() == source.component_types().len() {
+                return Vec::new();
+            }
 
-            self.components.get_mut(&component_container.component_type).unwrap()
+            self.find_or_create_archetype(remaining)
         };
 
-        vec.insert_raw(entity, &component_container.data);
+        self.move_entity(entity, location, target).1
     }
 
     pub fn component<T: 'static + Component>(&self, entity: Entity) -> Option<&T> {
         let component_type = Self::get_component_type::<T>();
+        let location = self.location(entity)?;
 
-        self.components.get(&component_type)?.get::<T>(entity)
+        self.archetypes[location.archetype].column(component_type)?.get::<T>(location.row)
     }
 
-    pub fn component_mut<T: 'static + Component>(&mut self, entity: Entity) -> Option<&mut T> {
+    pub fn component_mut<T: 'static + Component>(&mut self, entity: Entity) -> Option<Mut<'_, T>> {
         let component_type = Self::get_component_type::<T>();
+        let location = self.location(entity)?;
+        let tick = *self.change_tick.get_mut();
 
-        self.components.get_mut(&component_type)?.get_mut::<T>(entity)
+        self.archetypes[location.archetype]
+            .column_mut(component_type)?
+            .get_mut::<T>(location.row, tick)
     }
 
     pub fn components<T: 'static + Component>(&self) -> impl Iterator<Item = (Entity, &T)> {
         let component_type = Self::get_component_type::<T>();
 
-        self.components.get(&component_type).unwrap().iter()
+        self.archetypes
+            .iter()
+            .filter_map(move |x| Some(x.entities().iter().cloned().zip(x.column(component_type)?.iter::<T>())))
+            .flatten()
     }
 
-    pub fn components_mut<T: 'static + Component>(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+    pub fn components_mut<T: 'static + Component>(&mut self) -> impl Iterator<Item = (Entity, Mut<'_, T>)> {
         let component_type = Self::get_component_type::<T>();
+        let tick = *self.change_tick.get_mut();
+
+        self.archetypes
+            .iter_mut()
+            .filter_map(move |x| {
+                let (entities, column) = x.entities_and_column_mut(component_type)?;
 
-        self.components.get_mut(&component_type).unwrap().iter_mut()
+                Some(entities.iter().cloned().zip(column.iter_mut::<T>(tick)))
+            })
+            .flatten()
     }
 
     pub fn has_component<T: 'static + Component>(&self, entity: Entity) -> bool {
         let component_type = Self::get_component_type::<T>();
 
-        if let Some(components) = self.components.get(&component_type) {
-            components.contains(entity)
+        if let Some(location) = self.location(entity) {
+            self.archetypes[location.archetype].has_component(component_type)
         } else {
             false
         }
@@ -158,13 +369,16 @@ impl World {
     pub fn add_resource<T: 'static>(&mut self, resource: T) {
         let resource_type = Self::get_resource_type::<T>();
 
-        self.resources.insert(resource_type, Box::new(resource));
+        let tick = *self.change_tick.get_mut();
+
+        self.resources
+            .insert(resource_type, UnsafeCell::new((Box::new(resource), ComponentTicks::new(tick))));
     }
 
     pub fn resource<T: 'static>(&self) -> Option<&T> {
         let resource_type = Self::get_resource_type::<T>();
 
-        let storage = self.resources.get(&resource_type)?;
+        let (storage, _) = unsafe { &*self.resources.get(&resource_type)?.get() };
 
         Some(storage.downcast_ref::<T>().unwrap())
     }
@@ -172,7 +386,22 @@ impl World {
     pub fn resource_mut<T: 'static>(&mut self) -> Option<&mut T> {
         let resource_type = Self::get_resource_type::<T>();
 
-        let storage = self.resources.get_mut(&resource_type)?;
+        let tick = *self.change_tick.get_mut();
+        let (storage, ticks) = self.resources.get_mut(&resource_type)?.get_mut();
+
+        ticks.set_changed(tick);
+
+        Some(storage.downcast_mut::<T>().unwrap())
+    }
+
+    // caller should ensure no other reference to the resource exists
+    #[allow(clippy::mut_from_ref)]
+    pub(super) unsafe fn resource_unchecked_mut<T: 'static>(&self, tick: u32) -> Option<&mut T> {
+        let resource_type = Self::get_resource_type::<T>();
+
+        let (storage, ticks) = &mut *self.resources.get(&resource_type)?.get();
+
+        ticks.set_changed(tick);
 
         Some(storage.downcast_mut::<T>().unwrap())
     }
@@ -180,18 +409,96 @@ impl World {
     pub fn take_resource<T: 'static>(&mut self) -> Option<T> {
         let resource_type = Self::get_resource_type::<T>();
 
-        Some(*self.resources.remove(&resource_type)?.downcast::<T>().unwrap())
+        Some(*self.resources.remove(&resource_type)?.into_inner().0.downcast::<T>().unwrap())
+    }
+
+    pub(super) fn resource_ticks<T: 'static>(&self) -> Option<&ComponentTicks> {
+        let resource_type = Self::get_resource_type::<T>();
+
+        Some(unsafe { &(*self.resources.get(&resource_type)?.get()).1 })
+    }
+
+    pub(super) fn change_tick(&self) -> u32 {
+        self.change_tick.load(Ordering::Relaxed)
+    }
+
+    // returns tick a system should run with. changes made after that, including ones outside of systems, get newer tick.
+    pub(super) fn increment_change_tick(&self) -> u32 {
+        self.change_tick.fetch_add(1, Ordering::Relaxed)
+    }
+
+    // clamps ticks of components, resources and systems not updated for a long time, so that they aren't considered
+    // new after change tick wraps around. runs every `CHECK_TICK_THRESHOLD` ticks.
+    fn check_change_ticks(&mut self) {
+        let change_tick = *self.change_tick.get_mut();
+        if change_tick.wrapping_sub(self.last_check_tick) < CHECK_TICK_THRESHOLD {
+            return;
+        }
+        self.last_check_tick = change_tick;
+
+        for archetype in &mut self.archetypes {
+            archetype.check_change_ticks(change_tick);
+        }
+
+        for (resource_type, resource) in &mut self.resources {
+            let (storage, ticks) = resource.get_mut();
+
+            ticks.check_ticks(change_tick);
+            if let Some(check) = self.resource_tick_checks.get(resource_type) {
+                check(storage.as_mut(), change_tick);
+            }
+        }
+
+        self.schedule.check_change_ticks(change_tick);
+        for (_, callback) in &mut self.pending {
+            callback.check_change_ticks(change_tick);
+        }
+    }
+
+    // registers function clamping ticks stored inside resource `T`, called with the resource and current tick
+    pub(super) fn add_resource_tick_check<T: 'static>(&mut self, check: fn(&mut dyn Any, u32)) {
+        self.resource_tick_checks.insert(Self::get_resource_type::<T>(), check);
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| x.location.is_some())
+            .map(|(id, x)| Entity {
+                id: id as u32,
+                generation: x.generation,
+            })
     }
 
-    pub fn entities(&self) -> impl Iterator<Item = Entity> {
-        (0..self.entities).map(|x| Entity { id: x })
+    pub(super) fn archetypes(&self) -> impl Iterator<Item = &Archetype> {
+        self.archetypes.iter()
     }
 
-    pub fn event<T>(&self) -> Option<&T>
+    // registers `Events<T>` resource which is updated every frame
+    pub fn add_event<T>(&mut self)
     where
         T: 'static,
     {
-        self.events.get(&Self::get_event_type::<T>()).map(|x| x.downcast_ref::<T>().unwrap())
+        let event_type = Self::get_event_type::<T>();
+        if self.events.contains_key(&event_type) {
+            return;
+        }
+
+        self.add_resource(Events::<T>::new());
+        self.add_resource_tick_check::<Events<T>>(|x, tick| x.downcast_mut::<Events<T>>().unwrap().check_change_ticks(tick));
+        self.events
+            .insert(event_type, |world| world.resource_mut::<Events<T>>().unwrap().update());
+    }
+
+    pub fn send_event<T>(&mut self, event: T)
+    where
+        T: 'static,
+    {
+        self.add_event::<T>();
+
+        let tick = self.change_tick();
+        self.resource_mut::<Events<T>>().unwrap().send(event, tick);
     }
 
     pub fn async_job<'w, Job, JobFut, Callback, Output>(&mut self, job: Job, callback: Callback)
@@ -211,7 +518,7 @@ impl World {
         core::mem::swap(&mut self.pending, &mut pending);
 
         let mut commands = Vec::new();
-        for (mut future, callback) in pending {
+        for (mut future, mut callback) in pending {
             if let Poll::Ready(x) = poll!(&mut future) {
                 commands.extend(callback.run(self, Some(&*x)).commands.into_iter());
             } else {
@@ -219,52 +526,159 @@ impl World {
             }
         }
 
-        commands.extend(
-            self.systems
-                .iter()
-                .filter(|x| x.is_available(self))
-                .flat_map(|x| x.run(self, None).commands),
-        );
-
         self.run_commands(commands);
-        self.events.clear();
+
+        let mut schedule = core::mem::take(&mut self.schedule);
+        for (_, systems) in schedule.stages_mut() {
+            for _ in 0..systems.run_count(self) {
+                let commands = executor::run_stage(self, systems).await;
+
+                self.run_commands(commands);
+            }
+        }
+        self.schedule = schedule;
+
+        let updaters = self.events.values().cloned().collect::<Vec<_>>();
+        for updater in updaters {
+            updater(self);
+        }
+
+        self.check_change_ticks();
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn on_event<EventT>(&mut self, event: EventT)
-    where
-        EventT: 'static,
-    {
-        let event_type = Self::get_event_type::<EventT>();
+    // runs single stage, used for stages not run on every frame
+    pub(crate) async fn run_stage(&mut self, stage: Stage) {
+        let mut schedule = core::mem::take(&mut self.schedule);
+        if let Some(systems) = schedule.stage_mut(stage) {
+            let commands = executor::run_stage(self, systems).await;
 
-        self.events.insert(event_type, Box::new(event));
+            self.run_commands(commands);
+        }
+        self.schedule = schedule;
     }
 
-    fn run_commands(&mut self, commands: Vec<Command>) {
+    pub(crate) fn run_commands(&mut self, commands: Vec<Box<dyn Command>>) {
+        self.flush_entities();
+
         for command in commands {
-            match command {
-                Command::CreateEntity(components) => {
-                    let entity = self.spawn().entity();
-                    for component in components {
-                        self.add_component_raw(entity, component);
-                    }
-                }
-                Command::DestroyEntity(entity) => self.destroy(entity),
-                Command::CreateComponent(entity, components) => {
-                    for component in components {
-                        self.add_component_raw(entity, component);
-                    }
-                }
-                Command::DestroyComponent(_) => (), // TOOD
-            }
+            command.apply(self);
         }
     }
 
+    // sets how many times `stage` runs each frame. `run_count` is called every frame right before the stage runs.
+    pub fn set_stage_run_count(&mut self, stage: Stage, run_count: RunCount) {
+        self.schedule.set_run_count(stage, run_count);
+    }
+
     pub fn add_system<T, P>(&mut self, system: T)
     where
-        T: IntoSystem<P>,
+        T: IntoSystemDescriptor<P>,
     {
-        self.systems.push(system.into_system());
+        self.add_system_to_stage(Stage::Update, system);
+    }
+
+    pub fn add_system_to_stage<T, P>(&mut self, stage: Stage, system: T)
+    where
+        T: IntoSystemDescriptor<P>,
+    {
+        self.schedule.add_system(stage, system.into_descriptor());
+    }
+
+    fn location(&self, entity: Entity) -> Option<EntityLocation> {
+        let meta = self.entities.get(entity.id as usize)?;
+
+        if meta.generation == entity.generation {
+            meta.location
+        } else {
+            None
+        }
+    }
+
+    // flushes reserved entities so they can be written to. components added to dead entity are dropped.
+    fn alive_location(&mut self, entity: Entity) -> Option<EntityLocation> {
+        self.flush_entities();
+
+        let location = self.location(entity);
+        if location.is_none() {
+            log::warn!("adding components to dead entity {}", entity.id);
+        }
+
+        location
+    }
+
+    // returns archetype having components of `source` plus `component_type`
+    fn archetype_with(&mut self, source: ArchetypeId, component_type: ComponentType) -> ArchetypeId {
+        if let Some(x) = self.archetypes[source].add_edge(component_type) {
+            return x;
+        }
+
+        let mut component_types = self.archetypes[source].component_types().to_vec();
+        if let Err(pos) = component_types.binary_search(&component_type) {
+            component_types.insert(pos, component_type);
+        }
+
+        let target = self.find_or_create_archetype(component_types);
+        self.archetypes[source].set_add_edge(component_type, target);
+
+        target
+    }
+
+    // returns archetype having components of `source` except `component_type`
+    fn archetype_without(&mut self, source: ArchetypeId, component_type: ComponentType) -> ArchetypeId {
+        if let Some(x) = self.archetypes[source].remove_edge(component_type) {
+            return x;
+        }
+
+        let component_types = self.archetypes[source]
+            .component_types()
+            .iter()
+            .filter(|&&x| x != component_type)
+            .cloned()
+            .collect();
+
+        let target = self.find_or_create_archetype(component_types);
+        self.archetypes[source].set_remove_edge(component_type, target);
+
+        target
+    }
+
+    // `component_types` should be sorted
+    fn find_or_create_archetype(&mut self, component_types: Vec<ComponentType>) -> ArchetypeId {
+        if let Some(&x) = self.archetype_ids.get(&component_types) {
+            return x;
+        }
+
+        let type_descriptors = component_types.iter().map(|x| &self.type_descriptors[x]);
+        let archetype = Archetype::new(component_types.clone(), type_descriptors);
+
+        let id = self.archetypes.len();
+        self.archetypes.push(archetype);
+        self.archetype_ids.insert(component_types, id);
+
+        id
+    }
+
+    // returns new location and components not in `target` archetype
+    fn move_entity(&mut self, entity: Entity, location: EntityLocation, target: ArchetypeId) -> (EntityLocation, Vec<ComponentContainer>) {
+        let (source_archetype, target_archetype) = if location.archetype < target {
+            let (left, right) = self.archetypes.split_at_mut(target);
+
+            (&mut left[location.archetype], &mut right[0])
+        } else {
+            let (left, right) = self.archetypes.split_at_mut(location.archetype);
+
+            (&mut right[0], &mut left[target])
+        };
+
+        let (row, moved, removed) = source_archetype.move_to(location.row, target_archetype);
+        if let Some(moved) = moved {
+            self.entities[moved.id as usize].location = Some(location);
+        }
+
+        let new_location = EntityLocation { archetype: target, row };
+        self.entities[entity.id as usize].location = Some(new_location);
+
+        (new_location, removed)
     }
 
     fn get_component_type<ComponentT>() -> ComponentType
@@ -274,7 +688,7 @@ impl World {
         TypeId::of::<ComponentT>()
     }
 
-    fn get_resource_type<ResourceT>() -> ResourceType
+    pub(super) fn get_resource_type<ResourceT>() -> ResourceType
     where
         ResourceT: 'static,
     {
@@ -297,11 +711,12 @@ impl Default for World {
 
 #[cfg(test)]
 mod test {
-    use alloc::{vec, vec::Vec};
+    use alloc::{sync::Arc, vec, vec::Vec};
+    use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
-    use crate::ecs::CommandList;
+    use crate::ecs::{Changed, CommandList, Query, ResMut, Resource};
 
-    use super::{Component, World};
+    use super::{Component, Entity, World, CHECK_TICK_THRESHOLD};
 
     #[test]
     fn test_entity() {
@@ -461,6 +876,84 @@ mod test {
         assert!(world.component::<TestComponent>(entity).is_none());
     }
 
+    #[test]
+    fn test_component_overwrite() {
+        struct TestComponent {
+            test: u32,
+        }
+
+        impl Component for TestComponent {}
+
+        let mut world = World::new();
+        let entity = world.spawn().with(TestComponent { test: 1 }).with(TestComponent { test: 2 }).entity();
+
+        assert_eq!(world.component::<TestComponent>(entity).unwrap().test, 2);
+        assert_eq!(world.components::<TestComponent>().count(), 1);
+    }
+
+    #[test]
+    fn test_archetype_move() {
+        struct TestComponent1 {
+            a: u32,
+        }
+        impl Component for TestComponent1 {}
+        struct TestComponent2 {
+            a: u32,
+        }
+        impl Component for TestComponent2 {}
+
+        let mut world = World::new();
+
+        let entity1 = world.spawn().with(TestComponent1 { a: 1 }).with(TestComponent2 { a: 2 }).entity();
+        let entity2 = world.spawn().with(TestComponent2 { a: 3 }).with(TestComponent1 { a: 4 }).entity();
+        let entity3 = world.spawn().with(TestComponent1 { a: 5 }).entity();
+        let entity4 = world.spawn().with(TestComponent1 { a: 6 }).entity();
+
+        world.add_component(entity3, TestComponent2 { a: 7 });
+        world.destroy(entity1);
+
+        assert!(world.component::<TestComponent1>(entity1).is_none());
+        assert_eq!(world.component::<TestComponent1>(entity2).unwrap().a, 4);
+        assert_eq!(world.component::<TestComponent2>(entity2).unwrap().a, 3);
+        assert_eq!(world.component::<TestComponent1>(entity3).unwrap().a, 5);
+        assert_eq!(world.component::<TestComponent2>(entity3).unwrap().a, 7);
+        assert_eq!(world.component::<TestComponent1>(entity4).unwrap().a, 6);
+        assert!(!world.has_component::<TestComponent2>(entity4));
+
+        assert_eq!(world.components::<TestComponent1>().count(), 3);
+        assert_eq!(world.components::<TestComponent2>().count(), 2);
+    }
+
+    #[test]
+    fn test_entity_recycle() {
+        struct TestComponent {
+            test: u32,
+        }
+
+        impl Component for TestComponent {}
+
+        let mut world = World::new();
+        let entity1 = world.spawn().with(TestComponent { test: 1 }).entity();
+
+        world.destroy(entity1);
+        assert!(!world.is_alive(entity1));
+        assert_eq!(world.entities().count(), 0);
+
+        let entity2 = world.spawn().with(TestComponent { test: 2 }).entity();
+        assert!(entity1 != entity2);
+        assert_eq!(entity1.id, entity2.id);
+        assert!(world.is_alive(entity2));
+
+        assert!(world.component::<TestComponent>(entity1).is_none());
+        assert!(world.component_mut::<TestComponent>(entity1).is_none());
+        assert!(!world.has_component::<TestComponent>(entity1));
+        assert_eq!(world.component::<TestComponent>(entity2).unwrap().test, 2);
+
+        // destroying stale handle should not affect recycled entity
+        world.destroy(entity1);
+        assert!(world.is_alive(entity2));
+    }
+
     #[tokio::test]
     async fn test_async() {
         struct TestComponent {
@@ -504,4 +997,164 @@ mod test {
 
         assert_eq!(world.component::<TestComponent2>(entity).unwrap().a, 2);
     }
+
+    #[test]
+    fn test_remove_component() {
+        struct TestComponent1 {
+            a: Vec<u32>,
+        }
+        impl Component for TestComponent1 {}
+        struct TestComponent2 {
+            a: u32,
+        }
+        impl Component for TestComponent2 {}
+
+        let mut world = World::new();
+        let entity = world
+            .spawn()
+            .with(TestComponent1 { a: vec![1, 2] })
+            .with(TestComponent2 { a: 3 })
+            .entity();
+
+        let component = world.remove_component::<TestComponent1>(entity).unwrap();
+        assert_eq!(component.a, [1, 2]);
+
+        assert!(!world.has_component::<TestComponent1>(entity));
+        assert!(world.remove_component::<TestComponent1>(entity).is_none());
+        assert_eq!(world.component::<TestComponent2>(entity).unwrap().a, 3);
+    }
+
+    #[test]
+    fn test_remove_component_command() {
+        let dropped = Arc::new(AtomicBool::new(false));
+
+        struct TestComponent1 {
+            dropped: Arc<AtomicBool>,
+        }
+        impl Component for TestComponent1 {}
+        impl Drop for TestComponent1 {
+            fn drop(&mut self) {
+                self.dropped.store(true, Ordering::Relaxed);
+            }
+        }
+        struct TestComponent2 {
+            a: u32,
+        }
+        impl Component for TestComponent2 {}
+
+        let mut world = World::new();
+        let entity = world
+            .spawn()
+            .with(TestComponent1 { dropped: dropped.clone() })
+            .with(TestComponent2 { a: 1 })
+            .entity();
+
+        let cmd_list = CommandList::new().remove_component::<TestComponent1>(entity);
+        world.run_commands(cmd_list.commands);
+
+        assert!(dropped.load(Ordering::Relaxed));
+        assert!(!world.has_component::<TestComponent1>(entity));
+        assert_eq!(world.component::<TestComponent2>(entity).unwrap().a, 1);
+    }
+
+    #[test]
+    fn test_component_container_drop() {
+        let drops = Arc::new(AtomicU32::new(0));
+
+        struct TestComponent {
+            drops: Arc<AtomicU32>,
+        }
+        impl Component for TestComponent {}
+        impl Drop for TestComponent {
+            fn drop(&mut self) {
+                self.drops.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        // unapplied container drops its component
+        drop(CommandList::new().create_entity((TestComponent { drops: drops.clone() },)));
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+        let mut world = World::new();
+        let cmd_list = CommandList::new().create_entity((TestComponent { drops: drops.clone() },));
+        world.run_commands(cmd_list.commands);
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+        drop(world);
+        assert_eq!(drops.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_reserve_entity() {
+        struct TestComponent {
+            target: Entity,
+        }
+        impl Component for TestComponent {}
+
+        let mut world = World::new();
+        let destroyed = world.spawn().entity();
+        world.destroy(destroyed);
+
+        let entity1 = world.reserve_entity();
+        let entity2 = world.reserve_entity();
+        assert_eq!(entity1.id, destroyed.id);
+        assert!(entity1 != destroyed);
+        assert!(entity1 != entity2);
+        assert!(!world.is_alive(entity2));
+
+        let cmd_list = CommandList::new()
+            .add_bundle(entity1, (TestComponent { target: entity2 },))
+            .add_bundle(entity2, (TestComponent { target: entity1 },));
+        world.run_commands(cmd_list.commands);
+
+        assert!(world.is_alive(entity1));
+        assert!(world.is_alive(entity2));
+        assert!(world.component::<TestComponent>(entity1).unwrap().target == entity2);
+        assert!(world.component::<TestComponent>(entity2).unwrap().target == entity1);
+
+        let entity3 = world.spawn().entity();
+        assert!(entity3 != entity1 && entity3 != entity2);
+        assert_eq!(world.entities().count(), 3);
+
+        // reserved entity is flushed when component is added directly, and dead entity is ignored
+        let entity4 = world.reserve_entity();
+        world.add_component(entity4, TestComponent { target: entity1 });
+        assert!(world.component::<TestComponent>(entity4).unwrap().target == entity1);
+
+        world.add_component(destroyed, TestComponent { target: entity1 });
+        assert!(!world.is_alive(destroyed));
+    }
+
+    #[tokio::test]
+    async fn test_check_change_ticks() {
+        struct TestComponent {}
+        impl Component for TestComponent {}
+        struct TestResource {}
+        struct ChangeCount(u32);
+
+        let mut world = World::new();
+        world.spawn().with(TestComponent {}).entity();
+        world.add_resource(TestResource {});
+        world.add_resource(ChangeCount(0));
+
+        world.add_system(
+            |query: Query<Entity, Changed<TestComponent>>, resource: Resource<TestResource>, mut count: ResMut<ChangeCount>| {
+                count.get_mut().0 += query.iter().count() as u32 + resource.is_changed() as u32;
+
+                CommandList::new()
+            },
+        );
+
+        world.update().await;
+        assert_eq!(world.resource::<ChangeCount>().unwrap().0, 2);
+
+        // change tick wraps around while nothing is changed
+        for _ in 0..10 {
+            let change_tick = world.change_tick.get_mut();
+            *change_tick = change_tick.wrapping_add(CHECK_TICK_THRESHOLD);
+
+            world.update().await;
+            assert_eq!(world.resource::<ChangeCount>().unwrap().0, 2);
+        }
+    }
 }
@@ -0,0 +1,289 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use hashbrown::{HashMap, HashSet};
+
+use super::{Input, KeyCode, Mouse, MouseButton};
+use crate::ecs::{CommandList, ResMut, Resource};
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    // parses key code name like `Space`, `A` or `Key1`, or mouse button name like `MouseLeft`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "MouseLeft" => Some(Binding::Mouse(MouseButton::Left)),
+            "MouseRight" => Some(Binding::Mouse(MouseButton::Right)),
+            "MouseMiddle" => Some(Binding::Mouse(MouseButton::Middle)),
+            _ => KeyCode::from_name(name).map(Binding::Key),
+        }
+    }
+
+    fn pressed(&self, keys: &Input<KeyCode>, buttons: &Input<MouseButton>) -> bool {
+        match *self {
+            Binding::Key(x) => keys.pressed(x),
+            Binding::Mouse(x) => buttons.pressed(x),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AxisBinding {
+    Buttons { positive: Binding, negative: Binding },
+    MouseX,
+    MouseY,
+    Wheel,
+}
+
+impl AxisBinding {
+    fn value(&self, keys: &Input<KeyCode>, buttons: &Input<MouseButton>, mouse: &Mouse) -> f32 {
+        match *self {
+            AxisBinding::Buttons { positive, negative } => {
+                positive.pressed(keys, buttons) as u32 as f32 - negative.pressed(keys, buttons) as u32 as f32
+            }
+            AxisBinding::MouseX => mouse.delta.x,
+            AxisBinding::MouseY => mouse.delta.y,
+            AxisBinding::Wheel => mouse.wheel,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ActionMapError {
+    InvalidLine(usize),
+    UnknownBinding(usize, String),
+    #[cfg(not(target_arch = "wasm32"))]
+    Io(std::io::Error),
+}
+
+impl fmt::Display for ActionMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionMapError::InvalidLine(line) => write!(f, "invalid binding at line {}", line),
+            ActionMapError::UnknownBinding(line, name) => write!(f, "unknown binding {} at line {}", name, line),
+            #[cfg(not(target_arch = "wasm32"))]
+            ActionMapError::Io(x) => write!(f, "{}", x),
+        }
+    }
+}
+
+// binds named actions and axes to keys and buttons
+#[derive(Default)]
+pub struct ActionMap {
+    actions: HashMap<String, Vec<Binding>>,
+    axes: HashMap<String, Vec<AxisBinding>>,
+}
+
+impl ActionMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bind_action(mut self, name: &str, binding: Binding) -> Self {
+        self.actions.entry(name.to_string()).or_default().push(binding);
+
+        self
+    }
+
+    pub fn bind_axis(mut self, name: &str, binding: AxisBinding) -> Self {
+        self.axes.entry(name.to_string()).or_default().push(binding);
+
+        self
+    }
+
+    // parses line based config. each line binds action to one or more buttons, or axis to a pair of buttons or mouse motion.
+    //
+    // # comment
+    // action jump = Space MouseRight
+    // axis move_x = D A
+    // axis look_x = MouseX
+    pub fn parse(config: &str) -> Result<Self, ActionMapError> {
+        let mut result = Self::new();
+
+        for (index, line) in config.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (declaration, bindings) = line.split_once('=').ok_or(ActionMapError::InvalidLine(line_number))?;
+            let bindings = bindings.split_whitespace().collect::<Vec<_>>();
+            let binding = |name: &str| Binding::from_name(name).ok_or_else(|| ActionMapError::UnknownBinding(line_number, name.to_string()));
+
+            match declaration.split_whitespace().collect::<Vec<_>>()[..] {
+                ["action", name] if !bindings.is_empty() => {
+                    for &x in &bindings {
+                        result = result.bind_action(name, binding(x)?);
+                    }
+                }
+                ["axis", name] => {
+                    let axis_binding = match bindings[..] {
+                        ["MouseX"] => AxisBinding::MouseX,
+                        ["MouseY"] => AxisBinding::MouseY,
+                        ["Wheel"] => AxisBinding::Wheel,
+                        [positive, negative] => AxisBinding::Buttons {
+                            positive: binding(positive)?,
+                            negative: binding(negative)?,
+                        },
+                        _ => return Err(ActionMapError::InvalidLine(line_number)),
+                    };
+
+                    result = result.bind_axis(name, axis_binding);
+                }
+                _ => return Err(ActionMapError::InvalidLine(line_number)),
+            }
+        }
+
+        Ok(result)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self, ActionMapError> {
+        let config = std::fs::read_to_string(path).map_err(ActionMapError::Io)?;
+
+        Self::parse(&config)
+    }
+}
+
+// state of actions and axes of `ActionMap`, updated every frame
+#[derive(Default)]
+pub struct ActionState {
+    pressed: HashSet<String>,
+    just_pressed: HashSet<String>,
+    just_released: HashSet<String>,
+    axes: HashMap<String, f32>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: &str) -> bool {
+        self.pressed.contains(action)
+    }
+
+    pub fn just_pressed(&self, action: &str) -> bool {
+        self.just_pressed.contains(action)
+    }
+
+    pub fn just_released(&self, action: &str) -> bool {
+        self.just_released.contains(action)
+    }
+
+    pub fn axis(&self, axis: &str) -> f32 {
+        self.axes.get(axis).cloned().unwrap_or(0.0)
+    }
+
+    fn update(&mut self, action_map: &ActionMap, keys: &Input<KeyCode>, buttons: &Input<MouseButton>, mouse: &Mouse) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+
+        for (name, bindings) in &action_map.actions {
+            let pressed = bindings.iter().any(|x| x.pressed(keys, buttons));
+
+            if pressed && !self.pressed.contains(name) {
+                self.pressed.insert(name.clone());
+                self.just_pressed.insert(name.clone());
+            } else if !pressed && self.pressed.remove(name) {
+                self.just_released.insert(name.clone());
+            }
+        }
+
+        self.axes = action_map
+            .axes
+            .iter()
+            .map(|(name, bindings)| (name.clone(), bindings.iter().map(|x| x.value(keys, buttons, mouse)).sum()))
+            .collect();
+    }
+}
+
+pub(super) fn update_actions(
+    action_map: Resource<ActionMap>,
+    keys: Resource<Input<KeyCode>>,
+    buttons: Resource<Input<MouseButton>>,
+    mouse: Resource<Mouse>,
+    mut state: ResMut<ActionState>,
+) -> CommandList {
+    state.get_mut().update(action_map.get(), keys.get(), buttons.get(), mouse.get());
+
+    CommandList::new()
+}
+
+#[cfg(test)]
+mod test {
+    use glam::Vec2;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = "
+            # movement
+            action jump = Space MouseRight
+            axis move_x = D A
+            axis look_x = MouseX
+        ";
+
+        let action_map = ActionMap::parse(config).unwrap();
+
+        assert_eq!(
+            action_map.actions["jump"],
+            [Binding::Key(KeyCode::Space), Binding::Mouse(MouseButton::Right)]
+        );
+        assert_eq!(
+            action_map.axes["move_x"],
+            [AxisBinding::Buttons {
+                positive: Binding::Key(KeyCode::D),
+                negative: Binding::Key(KeyCode::A)
+            }]
+        );
+        assert_eq!(action_map.axes["look_x"], [AxisBinding::MouseX]);
+
+        assert!(matches!(ActionMap::parse("action jump = Foo"), Err(ActionMapError::UnknownBinding(1, _))));
+        assert!(matches!(ActionMap::parse("\naction jump"), Err(ActionMapError::InvalidLine(2))));
+    }
+
+    #[test]
+    fn test_action_state() {
+        let action_map = ActionMap::new()
+            .bind_action("jump", Binding::Key(KeyCode::Space))
+            .bind_axis(
+                "move_x",
+                AxisBinding::Buttons {
+                    positive: Binding::Key(KeyCode::D),
+                    negative: Binding::Key(KeyCode::A),
+                },
+            )
+            .bind_axis("look_x", AxisBinding::MouseX);
+
+        let mut keys = Input::new();
+        let buttons = Input::new();
+        let mouse = Mouse {
+            position: None,
+            delta: Vec2::new(3.0, 0.0),
+            wheel: 0.0,
+        };
+        let mut state = ActionState::default();
+
+        keys.press(KeyCode::Space);
+        keys.press(KeyCode::A);
+        state.update(&action_map, &keys, &buttons, &mouse);
+
+        assert!(state.pressed("jump"));
+        assert!(state.just_pressed("jump"));
+        assert_eq!(state.axis("move_x"), -1.0);
+        assert_eq!(state.axis("look_x"), 3.0);
+        assert_eq!(state.axis("unknown"), 0.0);
+
+        keys.release(KeyCode::Space);
+        state.update(&action_map, &keys, &buttons, &mouse);
+
+        assert!(!state.pressed("jump"));
+        assert!(state.just_released("jump"));
+    }
+}
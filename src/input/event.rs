@@ -0,0 +1,346 @@
+use windowing::WindowEvent;
+
+use crate::ecs::World;
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum KeyCode {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Key0,
+    Key1,
+    Key2,
+    Key3,
+    Key4,
+    Key5,
+    Key6,
+    Key7,
+    Key8,
+    Key9,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    Left,
+    Up,
+    Right,
+    Down,
+    Space,
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    Delete,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Shift,
+    Control,
+    Alt,
+    Unknown(u8),
+}
+
+impl KeyCode {
+    // converts virtual key code reported by window
+    pub fn from_raw(code: u8) -> Self {
+        const LETTERS: [KeyCode; 26] = [
+            KeyCode::A,
+            KeyCode::B,
+            KeyCode::C,
+            KeyCode::D,
+            KeyCode::E,
+            KeyCode::F,
+            KeyCode::G,
+            KeyCode::H,
+            KeyCode::I,
+            KeyCode::J,
+            KeyCode::K,
+            KeyCode::L,
+            KeyCode::M,
+            KeyCode::N,
+            KeyCode::O,
+            KeyCode::P,
+            KeyCode::Q,
+            KeyCode::R,
+            KeyCode::S,
+            KeyCode::T,
+            KeyCode::U,
+            KeyCode::V,
+            KeyCode::W,
+            KeyCode::X,
+            KeyCode::Y,
+            KeyCode::Z,
+        ];
+        const DIGITS: [KeyCode; 10] = [
+            KeyCode::Key0,
+            KeyCode::Key1,
+            KeyCode::Key2,
+            KeyCode::Key3,
+            KeyCode::Key4,
+            KeyCode::Key5,
+            KeyCode::Key6,
+            KeyCode::Key7,
+            KeyCode::Key8,
+            KeyCode::Key9,
+        ];
+        const FUNCTIONS: [KeyCode; 12] = [
+            KeyCode::F1,
+            KeyCode::F2,
+            KeyCode::F3,
+            KeyCode::F4,
+            KeyCode::F5,
+            KeyCode::F6,
+            KeyCode::F7,
+            KeyCode::F8,
+            KeyCode::F9,
+            KeyCode::F10,
+            KeyCode::F11,
+            KeyCode::F12,
+        ];
+
+        match code {
+            b'A'..=b'Z' => LETTERS[(code - b'A') as usize],
+            b'0'..=b'9' => DIGITS[(code - b'0') as usize],
+            0x70..=0x7b => FUNCTIONS[(code - 0x70) as usize],
+            0x08 => KeyCode::Backspace,
+            0x09 => KeyCode::Tab,
+            0x0d => KeyCode::Enter,
+            0x10 => KeyCode::Shift,
+            0x11 => KeyCode::Control,
+            0x12 => KeyCode::Alt,
+            0x1b => KeyCode::Escape,
+            0x20 => KeyCode::Space,
+            0x21 => KeyCode::PageUp,
+            0x22 => KeyCode::PageDown,
+            0x23 => KeyCode::End,
+            0x24 => KeyCode::Home,
+            0x25 => KeyCode::Left,
+            0x26 => KeyCode::Up,
+            0x27 => KeyCode::Right,
+            0x28 => KeyCode::Down,
+            0x2d => KeyCode::Insert,
+            0x2e => KeyCode::Delete,
+            _ => KeyCode::Unknown(code),
+        }
+    }
+
+    // parses variant name like `Space`, `A` or `Key1`. `Unknown` codes have no name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "A" => KeyCode::A,
+            "B" => KeyCode::B,
+            "C" => KeyCode::C,
+            "D" => KeyCode::D,
+            "E" => KeyCode::E,
+            "F" => KeyCode::F,
+            "G" => KeyCode::G,
+            "H" => KeyCode::H,
+            "I" => KeyCode::I,
+            "J" => KeyCode::J,
+            "K" => KeyCode::K,
+            "L" => KeyCode::L,
+            "M" => KeyCode::M,
+            "N" => KeyCode::N,
+            "O" => KeyCode::O,
+            "P" => KeyCode::P,
+            "Q" => KeyCode::Q,
+            "R" => KeyCode::R,
+            "S" => KeyCode::S,
+            "T" => KeyCode::T,
+            "U" => KeyCode::U,
+            "V" => KeyCode::V,
+            "W" => KeyCode::W,
+            "X" => KeyCode::X,
+            "Y" => KeyCode::Y,
+            "Z" => KeyCode::Z,
+            "Key0" => KeyCode::Key0,
+            "Key1" => KeyCode::Key1,
+            "Key2" => KeyCode::Key2,
+            "Key3" => KeyCode::Key3,
+            "Key4" => KeyCode::Key4,
+            "Key5" => KeyCode::Key5,
+            "Key6" => KeyCode::Key6,
+            "Key7" => KeyCode::Key7,
+            "Key8" => KeyCode::Key8,
+            "Key9" => KeyCode::Key9,
+            "F1" => KeyCode::F1,
+            "F2" => KeyCode::F2,
+            "F3" => KeyCode::F3,
+            "F4" => KeyCode::F4,
+            "F5" => KeyCode::F5,
+            "F6" => KeyCode::F6,
+            "F7" => KeyCode::F7,
+            "F8" => KeyCode::F8,
+            "F9" => KeyCode::F9,
+            "F10" => KeyCode::F10,
+            "F11" => KeyCode::F11,
+            "F12" => KeyCode::F12,
+            "Left" => KeyCode::Left,
+            "Up" => KeyCode::Up,
+            "Right" => KeyCode::Right,
+            "Down" => KeyCode::Down,
+            "Space" => KeyCode::Space,
+            "Enter" => KeyCode::Enter,
+            "Escape" => KeyCode::Escape,
+            "Tab" => KeyCode::Tab,
+            "Backspace" => KeyCode::Backspace,
+            "Delete" => KeyCode::Delete,
+            "Insert" => KeyCode::Insert,
+            "Home" => KeyCode::Home,
+            "End" => KeyCode::End,
+            "PageUp" => KeyCode::PageUp,
+            "PageDown" => KeyCode::PageDown,
+            "Shift" => KeyCode::Shift,
+            "Control" => KeyCode::Control,
+            "Alt" => KeyCode::Alt,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Other(u8),
+}
+
+impl MouseButton {
+    pub fn from_raw(button: u8) -> Self {
+        match button {
+            0 => MouseButton::Left,
+            1 => MouseButton::Right,
+            2 => MouseButton::Middle,
+            x => MouseButton::Other(x),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum KeyboardEvent {
+    KeyDown(KeyCode),
+    KeyUp(KeyCode),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MouseEvent {
+    Move { x: f32, y: f32 },
+    ButtonDown(MouseButton),
+    ButtonUp(MouseButton),
+    Wheel { delta: f32 },
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct TextInputEvent {
+    pub character: char,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct FocusEvent {
+    pub focused: bool,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct WindowResizedEvent {
+    pub width: u32,
+    pub height: u32,
+}
+
+pub(super) fn add_events(world: &mut World) {
+    world.add_event::<KeyboardEvent>();
+    world.add_event::<MouseEvent>();
+    world.add_event::<TextInputEvent>();
+    world.add_event::<FocusEvent>();
+    world.add_event::<WindowResizedEvent>();
+}
+
+// translates window event to ecs events
+pub(crate) fn send_window_event(world: &mut World, event: WindowEvent) {
+    match event {
+        WindowEvent::Paint => {}
+        WindowEvent::Resize(width, height) => world.send_event(WindowResizedEvent { width, height }),
+        WindowEvent::KeyDown(x) => world.send_event(KeyboardEvent::KeyDown(KeyCode::from_raw(x))),
+        WindowEvent::KeyUp(x) => world.send_event(KeyboardEvent::KeyUp(KeyCode::from_raw(x))),
+        WindowEvent::Char(character) => world.send_event(TextInputEvent { character }),
+        WindowEvent::MouseMove(x, y) => world.send_event(MouseEvent::Move { x: x as f32, y: y as f32 }),
+        WindowEvent::MouseDown(x) => world.send_event(MouseEvent::ButtonDown(MouseButton::from_raw(x))),
+        WindowEvent::MouseUp(x) => world.send_event(MouseEvent::ButtonUp(MouseButton::from_raw(x))),
+        WindowEvent::MouseWheel(delta) => world.send_event(MouseEvent::Wheel { delta: delta as f32 }),
+        WindowEvent::Focused(focused) => world.send_event(FocusEvent { focused }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecs::Events;
+
+    #[test]
+    fn test_key_code() {
+        assert_eq!(KeyCode::from_raw(b'W'), KeyCode::W);
+        assert_eq!(KeyCode::from_raw(b'7'), KeyCode::Key7);
+        assert_eq!(KeyCode::from_raw(0x7b), KeyCode::F12);
+        assert_eq!(KeyCode::from_raw(0x25), KeyCode::Left);
+        assert_eq!(KeyCode::from_raw(0xff), KeyCode::Unknown(0xff));
+    }
+
+    #[test]
+    fn test_key_code_name() {
+        assert_eq!(KeyCode::from_name("PageUp"), Some(KeyCode::PageUp));
+        assert_eq!(KeyCode::from_name("Unknown"), None);
+
+        // every named key code can be parsed back from its name
+        for code in (0..=u8::MAX).map(KeyCode::from_raw).filter(|x| !matches!(x, KeyCode::Unknown(_))) {
+            assert_eq!(KeyCode::from_name(&format!("{:?}", code)), Some(code));
+        }
+    }
+
+    #[test]
+    fn test_send_window_event() {
+        let mut world = World::new();
+        add_events(&mut world);
+
+        send_window_event(&mut world, WindowEvent::KeyDown(b'A'));
+        send_window_event(&mut world, WindowEvent::Resize(640, 480));
+
+        let keyboard = world.resource::<Events<KeyboardEvent>>().unwrap();
+        assert_eq!(keyboard.iter().collect::<Vec<_>>(), [&KeyboardEvent::KeyDown(KeyCode::A)]);
+
+        let resized = world.resource::<Events<WindowResizedEvent>>().unwrap();
+        assert_eq!(resized.iter().collect::<Vec<_>>(), [&WindowResizedEvent { width: 640, height: 480 }]);
+    }
+}
@@ -0,0 +1,64 @@
+mod action;
+mod event;
+mod state;
+
+pub use action::{ActionMap, ActionMapError, ActionState, AxisBinding, Binding};
+pub use event::{FocusEvent, KeyCode, KeyboardEvent, MouseButton, MouseEvent, TextInputEvent, WindowResizedEvent};
+pub use state::{Input, Mouse};
+
+pub(crate) use event::send_window_event;
+
+use crate::{
+    ecs::{IntoSystemDescriptor, Stage, World},
+    App, Plugin,
+};
+
+pub struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        init(app.world_mut());
+    }
+}
+
+// registers input events, state resources and systems updating them
+pub(crate) fn init(world: &mut World) {
+    event::add_events(world);
+
+    world.add_resource(Input::<KeyCode>::new());
+    world.add_resource(Input::<MouseButton>::new());
+    world.add_resource(Mouse::default());
+    world.add_resource(ActionMap::new());
+    world.add_resource(ActionState::default());
+
+    world.add_system_to_stage(Stage::PreUpdate, state::update_input.label("input"));
+    world.add_system_to_stage(Stage::PreUpdate, action::update_actions.after("input"));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_input_systems() {
+        let mut world = World::new();
+        init(&mut world);
+        world.add_resource(ActionMap::new().bind_action("jump", Binding::Key(KeyCode::Space)));
+
+        world.send_event(KeyboardEvent::KeyDown(KeyCode::Space));
+        world.send_event(MouseEvent::Move { x: 10.0, y: 10.0 });
+        world.send_event(MouseEvent::Move { x: 15.0, y: 8.0 });
+        world.update().await;
+
+        assert!(world.resource::<Input<KeyCode>>().unwrap().just_pressed(KeyCode::Space));
+        assert!(world.resource::<ActionState>().unwrap().just_pressed("jump"));
+        assert_eq!(world.resource::<Mouse>().unwrap().delta, glam::Vec2::new(5.0, -2.0));
+
+        world.send_event(KeyboardEvent::KeyUp(KeyCode::Space));
+        world.update().await;
+
+        assert!(world.resource::<Input<KeyCode>>().unwrap().just_released(KeyCode::Space));
+        assert!(world.resource::<ActionState>().unwrap().just_released("jump"));
+        assert_eq!(world.resource::<Mouse>().unwrap().delta, glam::Vec2::ZERO);
+    }
+}
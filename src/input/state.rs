@@ -0,0 +1,156 @@
+use core::hash::Hash;
+
+use glam::Vec2;
+use hashbrown::HashSet;
+
+use super::{FocusEvent, KeyCode, KeyboardEvent, MouseButton, MouseEvent};
+use crate::ecs::{CommandList, EventReader, ResMut};
+
+// pressed state of keys or buttons. `just_pressed` and `just_released` are kept for one frame.
+pub struct Input<T> {
+    pressed: HashSet<T>,
+    just_pressed: HashSet<T>,
+    just_released: HashSet<T>,
+}
+
+impl<T> Input<T>
+where
+    T: Copy + Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self {
+            pressed: HashSet::new(),
+            just_pressed: HashSet::new(),
+            just_released: HashSet::new(),
+        }
+    }
+
+    pub fn pressed(&self, input: T) -> bool {
+        self.pressed.contains(&input)
+    }
+
+    pub fn just_pressed(&self, input: T) -> bool {
+        self.just_pressed.contains(&input)
+    }
+
+    pub fn just_released(&self, input: T) -> bool {
+        self.just_released.contains(&input)
+    }
+
+    pub fn get_pressed(&self) -> impl Iterator<Item = T> + '_ {
+        self.pressed.iter().cloned()
+    }
+
+    pub fn press(&mut self, input: T) {
+        if self.pressed.insert(input) {
+            self.just_pressed.insert(input);
+        }
+    }
+
+    pub fn release(&mut self, input: T) {
+        if self.pressed.remove(&input) {
+            self.just_released.insert(input);
+        }
+    }
+
+    pub fn release_all(&mut self) {
+        self.just_released.extend(self.pressed.drain());
+    }
+
+    // clears per frame state
+    pub fn clear(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+    }
+}
+
+impl<T> Default for Input<T>
+where
+    T: Copy + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// cursor position in window coordinates. `delta` and `wheel` are accumulated over a frame.
+#[derive(Default)]
+pub struct Mouse {
+    pub position: Option<Vec2>,
+    pub delta: Vec2,
+    pub wheel: f32,
+}
+
+pub(super) fn update_input(
+    keyboard_events: EventReader<KeyboardEvent>,
+    mouse_events: EventReader<MouseEvent>,
+    focus_events: EventReader<FocusEvent>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut buttons: ResMut<Input<MouseButton>>,
+    mut mouse: ResMut<Mouse>,
+) -> CommandList {
+    let keys = keys.get_mut();
+    let buttons = buttons.get_mut();
+    let mouse = mouse.get_mut();
+
+    keys.clear();
+    buttons.clear();
+    mouse.delta = Vec2::ZERO;
+    mouse.wheel = 0.0;
+
+    for event in keyboard_events.iter() {
+        match *event {
+            KeyboardEvent::KeyDown(x) => keys.press(x),
+            KeyboardEvent::KeyUp(x) => keys.release(x),
+        }
+    }
+
+    for event in mouse_events.iter() {
+        match *event {
+            MouseEvent::Move { x, y } => {
+                let position = Vec2::new(x, y);
+                if let Some(previous) = mouse.position {
+                    mouse.delta += position - previous;
+                }
+                mouse.position = Some(position);
+            }
+            MouseEvent::ButtonDown(x) => buttons.press(x),
+            MouseEvent::ButtonUp(x) => buttons.release(x),
+            MouseEvent::Wheel { delta } => mouse.wheel += delta,
+        }
+    }
+
+    // release events are not delivered while unfocused
+    if focus_events.iter().any(|x| !x.focused) {
+        keys.release_all();
+        buttons.release_all();
+    }
+
+    CommandList::new()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let mut input = Input::new();
+
+        input.press(KeyCode::A);
+        assert!(input.pressed(KeyCode::A));
+        assert!(input.just_pressed(KeyCode::A));
+
+        input.clear();
+        input.press(KeyCode::A);
+        assert!(input.pressed(KeyCode::A));
+        assert!(!input.just_pressed(KeyCode::A));
+
+        input.release(KeyCode::A);
+        assert!(!input.pressed(KeyCode::A));
+        assert!(input.just_released(KeyCode::A));
+
+        input.clear();
+        assert!(!input.just_released(KeyCode::A));
+    }
+}
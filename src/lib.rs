@@ -2,9 +2,13 @@ extern crate alloc;
 
 mod app;
 pub mod ecs;
+pub mod input;
+mod plugin;
 pub mod render;
 mod task;
+pub mod time;
 pub mod ui;
 mod utils;
 
-pub use app::App;
+pub use app::{App, AppBuilder, AppConfig, AppExit, PluginInit};
+pub use plugin::{DefaultPlugins, Plugin, PluginGroup};
@@ -0,0 +1,94 @@
+use alloc::{boxed::Box, vec, vec::Vec};
+
+use super::{
+    input::InputPlugin,
+    render::{AssetPlugin, RenderPlugin},
+    time::TimePlugin,
+    ui::UiPlugin,
+    App,
+};
+
+// unit of app composition, adding resources, events and systems to app
+pub trait Plugin: 'static {
+    fn build(&self, app: &mut App);
+
+    // plugins with same name are added only once
+    fn name(&self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
+}
+
+pub trait PluginGroup {
+    fn plugins(self) -> Vec<Box<dyn Plugin>>;
+}
+
+// plugins added by `App::new`
+pub struct DefaultPlugins;
+
+impl PluginGroup for DefaultPlugins {
+    fn plugins(self) -> Vec<Box<dyn Plugin>> {
+        vec![
+            Box::new(TimePlugin),
+            Box::new(InputPlugin),
+            Box::new(AssetPlugin),
+            Box::new(RenderPlugin),
+            Box::new(UiPlugin),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        ecs::{CommandList, ResMut},
+        AppConfig, AppExit,
+    };
+
+    struct Counter {
+        a: u32,
+    }
+
+    struct CounterPlugin;
+
+    impl Plugin for CounterPlugin {
+        fn build(&self, app: &mut App) {
+            app.world_mut().add_resource(Counter { a: 0 });
+            app.world_mut().add_system(|mut counter: ResMut<Counter>| {
+                counter.get_mut().a += 1;
+
+                CommandList::new()
+            });
+        }
+    }
+
+    struct TestPlugins;
+
+    impl PluginGroup for TestPlugins {
+        fn plugins(self) -> Vec<Box<dyn Plugin>> {
+            vec![Box::new(TimePlugin), Box::new(CounterPlugin)]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plugin() {
+        let config = AppConfig {
+            headless: true,
+            ..AppConfig::default()
+        };
+
+        let exit = App::empty(config)
+            .await
+            .add_plugin(CounterPlugin)
+            .add_plugins(TestPlugins)
+            .on_shutdown(|counter: crate::ecs::Resource<Counter>| {
+                assert_eq!(counter.get().a, 3);
+
+                CommandList::new()
+            })
+            .run_frames(3)
+            .await;
+
+        assert_eq!(exit, AppExit::Success);
+    }
+}
@@ -1,6 +1,7 @@
-use core::cell::{RefCell, RefMut};
+use alloc::sync::Arc;
 
 use hashbrown::HashMap;
+use spinning_top::Spinlock;
 
 use super::{Renderer, Texture, TextureFormat};
 
@@ -16,7 +17,7 @@ struct TextureData {
 pub struct AssetLoader {
     last_id: u64,
     textures: HashMap<TextureAsset, TextureData>,
-    loaded_textures: RefCell<HashMap<TextureAsset, Texture>>,
+    loaded_textures: Spinlock<HashMap<TextureAsset, Arc<Texture>>>,
 }
 
 impl AssetLoader {
@@ -24,7 +25,7 @@ impl AssetLoader {
         Self {
             last_id: 0,
             textures: HashMap::new(),
-            loaded_textures: RefCell::new(HashMap::new()),
+            loaded_textures: Spinlock::new(HashMap::new()),
         }
     }
 
@@ -50,23 +51,17 @@ impl AssetLoader {
         id
     }
 
-    // should this function return Ref<Texture>?
-    pub fn texture(&self, renderer: &Renderer, id: TextureAsset) -> Option<RefMut<Texture>> {
-        let loaded_textures = self.loaded_textures.borrow_mut();
-
-        if !(loaded_textures.contains_key(&id) || self.textures.contains_key(&id)) {
-            return None;
+    // texture is created on first use. lock is not held while creating it, so concurrent first uses may create it twice,
+    // keeping the first one inserted.
+    pub fn texture(&self, renderer: &Renderer, id: TextureAsset) -> Option<Arc<Texture>> {
+        if let Some(x) = self.loaded_textures.lock().get(&id) {
+            return Some(x.clone());
         }
 
-        Some(RefMut::map(loaded_textures, |x| {
-            let entry = x.entry(id);
-
-            entry.or_insert_with(|| {
-                let x = self.textures.get(&id).unwrap();
+        let data = self.textures.get(&id)?;
+        let texture = Arc::new(Texture::with_texels(renderer, data.width, data.height, &data.texels, data.format));
 
-                Texture::with_texels(renderer, x.width, x.height, &x.texels, x.format)
-            })
-        }))
+        Some(self.loaded_textures.lock().entry(id).or_insert(texture).clone())
     }
 }
 
@@ -1,6 +1,8 @@
 use alloc::{boxed::Box, vec::Vec};
 use core::ops::Range;
 
+use glam::Mat4;
+
 use super::{transform::Transform, Camera, Material, Mesh};
 use crate::ecs::Component;
 
@@ -18,6 +20,13 @@ pub struct TransformComponent {
 
 impl Component for TransformComponent {}
 
+// world space transform computed from `TransformComponent` of the entity and its ancestors
+pub struct GlobalTransform {
+    pub matrix: Mat4,
+}
+
+impl Component for GlobalTransform {}
+
 pub struct CameraComponent {
     pub camera: Box<dyn Camera>,
 }
@@ -0,0 +1,70 @@
+use glam::Vec4;
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum PresentMode {
+    AutoVsync,
+    AutoNoVsync,
+    Fifo,
+    Immediate,
+    Mailbox,
+}
+
+impl PresentMode {
+    pub(crate) fn wgpu_mode(&self) -> wgpu::PresentMode {
+        match self {
+            PresentMode::AutoVsync => wgpu::PresentMode::AutoVsync,
+            PresentMode::AutoNoVsync => wgpu::PresentMode::AutoNoVsync,
+            PresentMode::Fifo => wgpu::PresentMode::Fifo,
+            PresentMode::Immediate => wgpu::PresentMode::Immediate,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum PowerPreference {
+    LowPower,
+    HighPerformance,
+}
+
+impl PowerPreference {
+    pub(crate) fn wgpu_preference(&self) -> wgpu::PowerPreference {
+        match self {
+            PowerPreference::LowPower => wgpu::PowerPreference::LowPower,
+            PowerPreference::HighPerformance => wgpu::PowerPreference::HighPerformance,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RenderConfig {
+    pub width: u32,
+    pub height: u32,
+    pub present_mode: PresentMode,
+    pub power_preference: PowerPreference,
+    // rgba, each in 0.0..=1.0
+    pub clear_color: Vec4,
+}
+
+impl RenderConfig {
+    pub(crate) fn wgpu_clear_color(&self) -> wgpu::Color {
+        wgpu::Color {
+            r: self.clear_color.x as f64,
+            g: self.clear_color.y as f64,
+            b: self.clear_color.z as f64,
+            a: self.clear_color.w as f64,
+        }
+    }
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            width: 1920,
+            height: 1080,
+            present_mode: PresentMode::AutoVsync,
+            power_preference: PowerPreference::LowPower,
+            clear_color: Vec4::ONE,
+        }
+    }
+}
@@ -4,10 +4,13 @@ mod buffer_pool;
 mod bundle;
 mod camera;
 mod components;
+mod config;
 mod constants;
 mod material;
 mod mesh;
 mod pipeline_cache;
+mod plugin;
+mod propagate;
 mod render_target;
 mod renderer;
 mod resource;
@@ -21,9 +24,12 @@ pub use asset::{AssetLoader, TextureAsset};
 pub use buffer::Buffer;
 pub use bundle::{RenderBundle, SpriteBundle};
 pub use camera::{ArcballCameraController, Camera, OrthographicCamera, PerspectiveCamera, StaticCameraController};
-pub use components::{CameraComponent, RenderComponent, TransformComponent};
+pub use components::{CameraComponent, GlobalTransform, RenderComponent, TransformComponent};
+pub use config::{PowerPreference, PresentMode, RenderConfig};
 pub use material::Material;
 pub use mesh::{Mesh, SimpleVertex};
+pub use plugin::{AssetPlugin, RenderPlugin};
+pub use propagate::propagate_transforms;
 pub use render_target::{RenderTarget, WindowRenderTarget};
 pub use renderer::Renderer;
 pub use resource::Resource;
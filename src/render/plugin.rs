@@ -0,0 +1,73 @@
+use alloc::boxed::Box;
+
+use futures::future::LocalBoxFuture;
+
+use super::{propagate_transforms, AssetLoader, CameraComponent, GlobalTransform, Renderer};
+use crate::{
+    ecs::{Changed, CommandList, Entity, EventReader, IntoSystemDescriptor, Query, ResMut, Stage, World},
+    input::WindowResizedEvent,
+    App, Plugin,
+};
+
+// renders into window, or into offscreen target on headless app
+pub struct RenderPlugin;
+
+impl Plugin for RenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_async_init(create_renderer);
+
+        let world = app.world_mut();
+        world.add_system_to_stage(Stage::PreUpdate, resize);
+        world.add_system_to_stage(Stage::PostUpdate, propagate_transforms);
+        world.add_system_to_stage(Stage::Render, detect_changes.label("detect_changes"));
+        world.add_system_to_stage(Stage::Render, render.after("detect_changes"));
+    }
+}
+
+pub struct AssetPlugin;
+
+impl Plugin for AssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.world_mut().add_resource(AssetLoader::new());
+    }
+}
+
+fn create_renderer(app: &mut App) -> LocalBoxFuture<'_, ()> {
+    Box::pin(async move {
+        let config = app.config().render;
+        let renderer = match app.window() {
+            Some(window) => Renderer::new(window, &config).await,
+            None => Renderer::headless(&config).await,
+        };
+
+        app.world_mut().add_resource(renderer);
+    })
+}
+
+// cameras take aspect ratio from render target size, so resizing renderer is enough
+fn resize(events: EventReader<WindowResizedEvent>, mut renderer: ResMut<Renderer>) -> CommandList {
+    if let Some(x) = events.iter().last() {
+        renderer.get_mut().resize(x.width, x.height);
+    }
+
+    CommandList::new()
+}
+
+fn detect_changes(
+    transforms: Query<Entity, Changed<GlobalTransform>>,
+    cameras: Query<Entity, Changed<CameraComponent>>,
+    mut renderer: ResMut<Renderer>,
+) -> CommandList {
+    if transforms.iter().next().is_some() || cameras.iter().next().is_some() {
+        renderer.get_mut().invalidate_transforms();
+    }
+
+    CommandList::new()
+}
+
+fn render(world: &mut World) {
+    let mut renderer = world.take_resource::<Renderer>().unwrap();
+    renderer.render_world(world);
+
+    world.add_resource(renderer);
+}
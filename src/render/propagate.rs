@@ -0,0 +1,102 @@
+use alloc::vec::Vec;
+
+use glam::Mat4;
+
+use super::components::{GlobalTransform, TransformComponent};
+use crate::ecs::{Entity, HierarchyExt, World};
+
+// computes world space transform of every entity having `TransformComponent` by walking the hierarchy from roots.
+// entities without `TransformComponent`, including roots, pass their parent's transform to their children.
+pub fn propagate_transforms(world: &mut World) {
+    let mut stack = world
+        .entities()
+        .filter(|&x| world.parent(x).is_none())
+        .map(|x| (x, Mat4::IDENTITY))
+        .collect::<Vec<_>>();
+
+    // explicit stack, so deep hierarchy can't overflow call stack
+    while let Some((entity, parent_matrix)) = stack.pop() {
+        let matrix = propagate(world, entity, parent_matrix);
+
+        stack.extend(world.children(entity).iter().map(|&x| (x, matrix)));
+    }
+}
+
+// updates `GlobalTransform` of the entity, returning matrix passed to its children
+fn propagate(world: &mut World, entity: Entity, parent_matrix: Mat4) -> Mat4 {
+    let transform = match world.component::<TransformComponent>(entity) {
+        Some(x) => x.transform.to_matrix(),
+        None => return parent_matrix,
+    };
+    let matrix = parent_matrix * transform;
+
+    // unchanged transforms are not written, so they aren't detected as changed
+    if let Some(mut x) = world.component_mut::<GlobalTransform>(entity) {
+        if x.matrix != matrix {
+            x.matrix = matrix;
+        }
+    } else {
+        world.add_component(entity, GlobalTransform { matrix });
+    }
+
+    matrix
+}
+
+#[cfg(test)]
+mod test {
+    use glam::Vec3;
+
+    use super::*;
+    use crate::render::Transform;
+
+    #[test]
+    fn test_propagate() {
+        let mut world = World::new();
+
+        let parent = world
+            .spawn()
+            .with(TransformComponent {
+                transform: Transform::with_values(Vec3::new(1.0, 0.0, 0.0), Vec3::ZERO, Vec3::new(2.0, 2.0, 2.0)),
+            })
+            .entity();
+        let group = world.spawn().entity();
+        let child = world
+            .spawn()
+            .with(TransformComponent {
+                transform: Transform::with_values(Vec3::new(0.0, 1.0, 0.0), Vec3::ZERO, Vec3::ONE),
+            })
+            .entity();
+
+        world.add_child(parent, group);
+        world.add_child(group, child);
+
+        propagate_transforms(&mut world);
+
+        let parent_matrix = world.component::<GlobalTransform>(parent).unwrap().matrix;
+        assert_eq!(parent_matrix.transform_point3(Vec3::ZERO), Vec3::new(1.0, 0.0, 0.0));
+
+        let child_matrix = world.component::<GlobalTransform>(child).unwrap().matrix;
+        assert_eq!(child_matrix.transform_point3(Vec3::ZERO), Vec3::new(1.0, 2.0, 0.0));
+        assert!(world.component::<GlobalTransform>(group).is_none());
+    }
+
+    #[test]
+    fn test_propagate_transformless_root() {
+        let mut world = World::new();
+
+        let root = world.spawn().entity();
+        let child = world
+            .spawn()
+            .with(TransformComponent {
+                transform: Transform::with_values(Vec3::new(0.0, 1.0, 0.0), Vec3::ZERO, Vec3::ONE),
+            })
+            .entity();
+        world.add_child(root, child);
+
+        propagate_transforms(&mut world);
+
+        let child_matrix = world.component::<GlobalTransform>(child).unwrap().matrix;
+        assert_eq!(child_matrix.transform_point3(Vec3::ZERO), Vec3::new(0.0, 1.0, 0.0));
+        assert!(world.component::<GlobalTransform>(root).is_none());
+    }
+}
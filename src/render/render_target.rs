@@ -1,4 +1,5 @@
 use super::{
+    config::PresentMode,
     constants::{INTERNAL_COLOR_ATTACHMENT_FORMAT, INTERNAL_DEPTH_ATTACHMENT_FORMAT},
     Texture,
 };
@@ -8,19 +9,25 @@ pub trait RenderTarget: Sync + Send {
     fn color_attachment(&self) -> &wgpu::TextureView;
     fn submit(&mut self);
     fn output_format(&self) -> wgpu::TextureFormat;
+    fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32);
 }
 
 pub struct WindowRenderTarget {
     texture_view: wgpu::TextureView,
     frame: Option<wgpu::SurfaceTexture>,
     surface: wgpu::Surface,
-    width: u32,
-    height: u32,
-    format: wgpu::TextureFormat,
+    config: wgpu::SurfaceConfiguration,
 }
 
 impl WindowRenderTarget {
-    pub(crate) fn new(surface: wgpu::Surface, adapter: &wgpu::Adapter, device: &wgpu::Device, width: u32, height: u32) -> Self {
+    pub(crate) fn new(
+        surface: wgpu::Surface,
+        adapter: &wgpu::Adapter,
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        present_mode: PresentMode,
+    ) -> Self {
         let format = surface.get_supported_formats(adapter)[0];
 
         let config = wgpu::SurfaceConfiguration {
@@ -28,7 +35,7 @@ impl WindowRenderTarget {
             format,
             width,
             height,
-            present_mode: wgpu::PresentMode::AutoVsync,
+            present_mode: present_mode.wgpu_mode(),
             alpha_mode: wgpu::CompositeAlphaMode::Auto,
         };
 
@@ -41,19 +48,17 @@ impl WindowRenderTarget {
             surface,
             frame: Some(frame),
             texture_view,
-            format,
-            width,
-            height,
+            config,
         }
     }
 }
 
 impl RenderTarget for WindowRenderTarget {
     fn size(&self) -> (u32, u32) {
-        (self.width, self.height)
+        (self.config.width, self.config.height)
     }
 
-    // blocks until next frame(PresentMode::AutoVsync)
+    // blocks until next frame on vsync present modes
     fn submit(&mut self) {
         self.frame.take().unwrap().present();
 
@@ -66,7 +71,19 @@ impl RenderTarget for WindowRenderTarget {
     }
 
     fn output_format(&self) -> wgpu::TextureFormat {
-        self.format
+        self.config.format
+    }
+
+    fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        // surface can't be reconfigured while its texture is alive
+        self.frame = None;
+
+        self.config.width = width;
+        self.config.height = height;
+        self.surface.configure(device, &self.config);
+
+        self.frame = Some(self.surface.get_current_texture().unwrap());
+        self.texture_view = self.frame.as_ref().unwrap().texture.create_view(&wgpu::TextureViewDescriptor::default());
     }
 }
 
@@ -106,4 +123,8 @@ impl RenderTarget for OffscreenRenderTarget {
     fn output_format(&self) -> wgpu::TextureFormat {
         INTERNAL_COLOR_ATTACHMENT_FORMAT.wgpu_format()
     }
+
+    fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        *self = Self::with_device(device, width, height);
+    }
 }
@@ -7,7 +7,8 @@ use zerocopy::AsBytes;
 use super::{
     buffer_pool::BufferPool,
     camera::Camera,
-    components::{CameraComponent, RenderComponent, TransformComponent},
+    components::{CameraComponent, GlobalTransform, RenderComponent},
+    config::RenderConfig,
     constants::INTERNAL_COLOR_ATTACHMENT_FORMAT,
     pipeline_cache::PipelineCache,
     render_target::OffscreenRenderTarget,
@@ -16,7 +17,7 @@ use super::{
     WindowRenderTarget,
 };
 use crate::{
-    ecs::{Entity, Query, World},
+    ecs::{Entity, Query, With, World},
     ui::UiComponent,
 };
 
@@ -43,18 +44,49 @@ pub struct Renderer {
     offscreen_render_material: Material,
     pub(crate) pipeline_cache: PipelineCache,
     pub empty_texture: Texture,
+    clear_color: wgpu::Color,
+    transforms_changed: bool,
+    rendered_entities: Vec<Entity>,
 }
 
 impl Renderer {
-    pub async fn new(window: &Window, width: u32, height: u32) -> Self {
+    pub async fn new(window: &Window, config: &RenderConfig) -> Self {
         let instance = wgpu::Instance::new(wgpu::Backends::all());
         let surface = unsafe { instance.create_surface(window) };
 
+        let (adapter, device, queue) = Self::request_device(&instance, config, Some(&surface)).await;
+        let render_target = Box::new(WindowRenderTarget::new(
+            surface,
+            &adapter,
+            &device,
+            config.width,
+            config.height,
+            config.present_mode,
+        ));
+
+        Self::with_device(device, queue, render_target, config)
+    }
+
+    // renders into offscreen target without window
+    pub async fn headless(config: &RenderConfig) -> Self {
+        let instance = wgpu::Instance::new(wgpu::Backends::all());
+
+        let (_, device, queue) = Self::request_device(&instance, config, None).await;
+        let render_target = Box::new(OffscreenRenderTarget::with_device(&device, config.width, config.height));
+
+        Self::with_device(device, queue, render_target, config)
+    }
+
+    async fn request_device(
+        instance: &wgpu::Instance,
+        config: &RenderConfig,
+        surface: Option<&wgpu::Surface>,
+    ) -> (wgpu::Adapter, wgpu::Device, wgpu::Queue) {
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(),
+                power_preference: config.power_preference.wgpu_preference(),
                 force_fallback_adapter: false,
-                compatible_surface: Some(&surface),
+                compatible_surface: surface,
             })
             .await
             .unwrap();
@@ -78,16 +110,21 @@ impl Renderer {
             .await
             .unwrap();
 
+        (adapter, device, queue)
+    }
+
+    fn with_device(device: wgpu::Device, queue: wgpu::Queue, render_target: Box<dyn RenderTarget>, config: &RenderConfig) -> Self {
+        let (width, height) = (config.width, config.height);
+
         let device = Arc::new(device);
         let queue = Arc::new(queue);
 
         let buffer_pool = BufferPool::new(device.clone(), queue.clone());
         let pipeline_cache = PipelineCache::new();
 
-        let render_target = Box::new(WindowRenderTarget::new(surface, &adapter, &device, width, height));
-
+        let offscreen_shader = Arc::new(Shader::with_device(&device, include_str!("./shaders/offscreen.wgsl")));
         let (offscreen_target, offscreen_render_mesh, offscreen_render_material) =
-            Self::create_offscreen_target(&device, &buffer_pool, width, height);
+            Self::create_offscreen_target(&device, &buffer_pool, offscreen_shader, width, height);
 
         let shader_transform = DynamicUniformBuffer::with_buffer_pool(&buffer_pool, 64); // TODO realloc
         let standard_shader = Arc::new(Shader::with_device(&device, include_str!("./shaders/standard.wgsl")));
@@ -106,22 +143,57 @@ impl Renderer {
             offscreen_render_material,
             pipeline_cache,
             empty_texture,
+            clear_color: config.wgpu_clear_color(),
+            transforms_changed: true,
+            rendered_entities: Vec::new(),
         }
     }
 
+    // reconfigures render target and recreates offscreen target for new size
+    pub fn resize(&mut self, width: u32, height: u32) {
+        // minimized window reports zero size, which can't be configured
+        if width == 0 || height == 0 || self.render_target.size() == (width, height) {
+            return;
+        }
+
+        self.render_target.resize(&self.device, width, height);
+        self.transforms_changed = true;
+
+        let shader = self.offscreen_render_material.shader.clone();
+        let (offscreen_target, offscreen_render_mesh, offscreen_render_material) =
+            Self::create_offscreen_target(&self.device, &self.buffer_pool, shader, width, height);
+
+        self.offscreen_target = offscreen_target;
+        self.offscreen_render_mesh = offscreen_render_mesh;
+        self.offscreen_render_material = offscreen_render_material;
+    }
+
+    // uploads transforms on next render. should be called when transforms or cameras changed.
+    pub fn invalidate_transforms(&mut self) {
+        self.transforms_changed = true;
+    }
+
     pub fn render_world(&mut self, world: &World) {
-        let entities = Query::<(RenderComponent, TransformComponent)>::new(world).iter().collect::<Vec<_>>();
+        let entities = Query::<(&RenderComponent, &GlobalTransform, Option<&UiComponent>)>::new(world)
+            .iter()
+            .collect::<Vec<_>>();
         let camera = &world.components::<CameraComponent>().next().unwrap().1.camera;
         let ui_camera = OrthographicCamera::new();
 
         let mut command_encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
 
-        self.write_transforms(world, camera.as_ref(), &ui_camera, &entities);
-
-        let render_components = entities
+        // uniform buffer keeps transforms of previous frame, so it's written only if they changed or entities differ
+        let rendered_entities = Query::<Entity, (With<RenderComponent>, With<GlobalTransform>)>::new(world)
             .iter()
-            .map(|&x| world.component::<RenderComponent>(x).unwrap())
             .collect::<Vec<_>>();
+        if self.transforms_changed || rendered_entities != self.rendered_entities {
+            self.write_transforms(camera.as_ref(), &ui_camera, &entities);
+
+            self.rendered_entities = rendered_entities;
+            self.transforms_changed = false;
+        }
+
+        let render_components = entities.iter().map(|x| x.0).collect::<Vec<_>>();
         self.render(&mut command_encoder, &render_components, self.render_target.size());
 
         self.present(&mut command_encoder, &*self.render_target);
@@ -130,22 +202,26 @@ impl Renderer {
         self.render_target.submit();
     }
 
-    fn write_transforms(&mut self, world: &World, camera: &dyn Camera, ui_camera: &dyn Camera, entities: &[Entity]) {
+    fn write_transforms(
+        &mut self,
+        camera: &dyn Camera,
+        ui_camera: &dyn Camera,
+        entities: &[(&RenderComponent, &GlobalTransform, Option<&UiComponent>)],
+    ) {
         let size = self.render_target.size();
 
         let transforms = entities
             .iter()
-            .map(|&entity| {
-                let transform = world.component::<TransformComponent>(entity).unwrap();
-                if world.component::<UiComponent>(entity).is_some() {
+            .map(|&(_, transform, ui)| {
+                if ui.is_some() {
                     ShaderTransform {
-                        model: transform.transform.to_matrix().to_cols_array(),
+                        model: transform.matrix.to_cols_array(),
                         view: ui_camera.view().to_cols_array(),
                         projection: ui_camera.projection(size.0, size.1).to_cols_array(),
                     }
                 } else {
                     ShaderTransform {
-                        model: transform.transform.to_matrix().to_cols_array(),
+                        model: transform.matrix.to_cols_array(),
                         view: camera.view().to_cols_array(),
                         projection: camera.projection(size.0, size.1).to_cols_array(),
                     }
@@ -177,7 +253,7 @@ impl Renderer {
                     view: self.offscreen_target.color_attachment(),
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color { r: 1., g: 1., b: 1., a: 1. }),
+                        load: wgpu::LoadOp::Clear(self.clear_color),
                         store: true,
                     },
                 })],
@@ -267,7 +343,13 @@ impl Renderer {
         }
     }
 
-    fn create_offscreen_target(device: &wgpu::Device, buffer_pool: &BufferPool, width: u32, height: u32) -> (OffscreenRenderTarget, Mesh, Material) {
+    fn create_offscreen_target(
+        device: &wgpu::Device,
+        buffer_pool: &BufferPool,
+        shader: Arc<Shader>,
+        width: u32,
+        height: u32,
+    ) -> (OffscreenRenderTarget, Mesh, Material) {
         let texture_width = width.next_power_of_two();
         let texture_height = height.next_power_of_two();
         let offscreen_target = OffscreenRenderTarget::with_device(device, texture_width, texture_height);
@@ -298,9 +380,7 @@ impl Renderer {
             )],
         );
 
-        let shader = Shader::with_device(device, include_str!("./shaders/offscreen.wgsl"));
-
-        let material = Material::with_device(device, None, &[("texture", &offscreen_target.color_attachment)], Arc::new(shader));
+        let material = Material::with_device(device, None, &[("texture", &offscreen_target.color_attachment)], shader);
 
         (offscreen_target, mesh, material)
     }
@@ -1,8 +1,10 @@
-use glam::{EulerRot, Mat4, Quat, Vec3};
+use glam::{EulerRot, Mat3, Mat4, Quat, Vec3};
 
+// right handed, -z forward and +y up
+#[derive(Clone, Copy)]
 pub struct Transform {
     pub translation: Vec3,
-    pub rotation: Vec3,
+    pub rotation: Quat,
     pub scale: Vec3,
 }
 
@@ -10,12 +12,21 @@ impl Transform {
     pub fn new() -> Self {
         Self {
             translation: Vec3::ZERO,
-            rotation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
             scale: Vec3::ONE,
         }
     }
 
+    // `rotation` is euler angles in radians, applied in yaw(y), pitch(x), roll(z) order
     pub fn with_values(translation: Vec3, rotation: Vec3, scale: Vec3) -> Self {
+        Self {
+            translation,
+            rotation: Self::quat_from_euler(rotation),
+            scale,
+        }
+    }
+
+    pub fn with_quat(translation: Vec3, rotation: Quat, scale: Vec3) -> Self {
         Self {
             translation,
             rotation,
@@ -23,24 +34,107 @@ impl Transform {
         }
     }
 
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self { translation, ..Self::new() }
+    }
+
     pub fn from_matrix(matrix: &Mat4) -> Self {
         let (scale, rotation, translation) = matrix.to_scale_rotation_translation();
-        let rotation = rotation.to_euler(EulerRot::YXZ);
 
         Self {
             translation,
-            rotation: Vec3::new(rotation.1, rotation.0, rotation.2),
+            rotation,
             scale,
         }
     }
 
     pub fn to_matrix(&self) -> Mat4 {
-        let rotation = Quat::from_euler(EulerRot::YXZ, self.rotation.y, self.rotation.x, self.rotation.z);
-        Mat4::from_scale_rotation_translation(self.scale, rotation, self.translation)
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+
+    pub fn euler_angles(&self) -> Vec3 {
+        let (y, x, z) = self.rotation.to_euler(EulerRot::YXZ);
+
+        Vec3::new(x, y, z)
+    }
+
+    pub fn forward(&self) -> Vec3 {
+        self.rotation * Vec3::NEG_Z
+    }
+
+    pub fn right(&self) -> Vec3 {
+        self.rotation * Vec3::X
+    }
+
+    pub fn up(&self) -> Vec3 {
+        self.rotation * Vec3::Y
     }
 
+    // rotates in local space by euler angles, applying `rotation` before current rotation
     pub fn rotate(&mut self, rotation: Vec3) {
-        self.rotation += rotation;
+        self.rotation = (self.rotation * Self::quat_from_euler(rotation)).normalize();
+    }
+
+    // rotates in world space, applying `rotation` after current rotation
+    pub fn rotate_quat(&mut self, rotation: Quat) {
+        self.rotation = (rotation * self.rotation).normalize();
+    }
+
+    // rotates around `point` in world space, changing both translation and rotation
+    pub fn rotate_around(&mut self, point: Vec3, rotation: Quat) {
+        self.translation = point + rotation * (self.translation - point);
+        self.rotate_quat(rotation);
+    }
+
+    // rotates so that forward points at `target`. rotation is kept if `target` is at translation, and any up orthogonal
+    // to forward is used if `up` is parallel to it.
+    pub fn look_at(&mut self, target: Vec3, up: Vec3) {
+        let back = match (self.translation - target).try_normalize() {
+            Some(x) => x,
+            None => return,
+        };
+        let right = up.cross(back).try_normalize().unwrap_or_else(|| back.any_orthonormal_vector());
+        let up = back.cross(right);
+
+        self.rotation = Quat::from_mat3(&Mat3::from_cols(right, up, back));
+    }
+
+    // returns transform applying `other` first, then `self`
+    pub fn mul_transform(&self, other: &Transform) -> Transform {
+        Self {
+            translation: self.transform_point(other.translation),
+            rotation: (self.rotation * other.rotation).normalize(),
+            scale: self.scale * other.scale,
+        }
+    }
+
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.transform_vector(point) + self.translation
+    }
+
+    pub fn transform_vector(&self, vector: Vec3) -> Vec3 {
+        self.rotation * (self.scale * vector)
+    }
+
+    // interpolates rotation linearly, which is cheaper than `slerp` but doesn't keep angular velocity constant
+    pub fn lerp(&self, other: &Transform, t: f32) -> Transform {
+        Self {
+            translation: self.translation.lerp(other.translation, t),
+            rotation: self.rotation.lerp(other.rotation, t),
+            scale: self.scale.lerp(other.scale, t),
+        }
+    }
+
+    pub fn slerp(&self, other: &Transform, t: f32) -> Transform {
+        Self {
+            translation: self.translation.lerp(other.translation, t),
+            rotation: self.rotation.slerp(other.rotation, t),
+            scale: self.scale.lerp(other.scale, t),
+        }
+    }
+
+    fn quat_from_euler(rotation: Vec3) -> Quat {
+        Quat::from_euler(EulerRot::YXZ, rotation.y, rotation.x, rotation.z)
     }
 }
 
@@ -49,3 +143,104 @@ impl Default for Transform {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use core::f32::consts::FRAC_PI_2;
+
+    use glam::{Quat, Vec3};
+
+    use super::Transform;
+
+    fn assert_near(a: Vec3, b: Vec3) {
+        assert!(a.abs_diff_eq(b, 1e-5), "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_euler() {
+        let rotation = Vec3::new(0.3, 0.5, 0.7);
+        let transform = Transform::with_values(Vec3::ZERO, rotation, Vec3::ONE);
+
+        assert_near(transform.euler_angles(), rotation);
+
+        let transform = Transform::from_matrix(&transform.to_matrix());
+        assert_near(transform.euler_angles(), rotation);
+    }
+
+    #[test]
+    fn test_axes() {
+        let transform = Transform::with_values(Vec3::ZERO, Vec3::new(0.0, FRAC_PI_2, 0.0), Vec3::ONE);
+
+        assert_near(transform.forward(), Vec3::NEG_X);
+        assert_near(transform.right(), Vec3::NEG_Z);
+        assert_near(transform.up(), Vec3::Y);
+    }
+
+    #[test]
+    fn test_look_at() {
+        let mut transform = Transform::from_translation(Vec3::new(1.0, 2.0, 3.0));
+        transform.look_at(Vec3::new(1.0, 2.0, 10.0), Vec3::Y);
+
+        assert_near(transform.forward(), Vec3::Z);
+        assert_near(transform.up(), Vec3::Y);
+
+        transform.look_at(transform.translation, Vec3::Y);
+        assert_near(transform.forward(), Vec3::Z);
+
+        transform.look_at(Vec3::new(1.0, 5.0, 3.0), Vec3::Y);
+        assert_near(transform.forward(), Vec3::Y);
+        assert!(transform.rotation.is_finite());
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut transform = Transform::with_values(Vec3::ZERO, Vec3::new(0.0, 0.5, 0.0), Vec3::ONE);
+        transform.rotate(Vec3::new(0.2, 0.0, 0.0));
+
+        assert_near(transform.euler_angles(), Vec3::new(0.2, 0.5, 0.0));
+
+        // pitch keeps going past straight up
+        let mut transform = Transform::new();
+        for _ in 0..3 {
+            transform.rotate(Vec3::new(0.6, 0.0, 0.0));
+        }
+
+        assert_near(transform.forward(), Quat::from_rotation_x(1.8) * Vec3::NEG_Z);
+    }
+
+    #[test]
+    fn test_rotate_around() {
+        let mut transform = Transform::from_translation(Vec3::new(2.0, 0.0, 0.0));
+        transform.rotate_around(Vec3::new(1.0, 0.0, 0.0), Quat::from_rotation_y(FRAC_PI_2));
+
+        assert_near(transform.translation, Vec3::new(1.0, 0.0, -1.0));
+        assert_near(transform.forward(), Vec3::NEG_X);
+    }
+
+    #[test]
+    fn test_mul_transform() {
+        let parent = Transform::with_values(Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, FRAC_PI_2, 0.0), Vec3::splat(2.0));
+        let child = Transform::with_values(Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.3, 0.0, 0.0), Vec3::ONE);
+
+        let point = Vec3::new(0.5, 0.2, 0.1);
+        let expected = (parent.to_matrix() * child.to_matrix()).transform_point3(point);
+
+        assert_near(parent.mul_transform(&child).transform_point(point), expected);
+        assert_near(parent.transform_point(Vec3::ZERO), Vec3::new(1.0, 0.0, 0.0));
+        assert_near(parent.transform_vector(Vec3::NEG_Z), Vec3::new(-2.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let a = Transform::new();
+        let b = Transform::with_quat(Vec3::new(2.0, 0.0, 0.0), Quat::from_rotation_y(FRAC_PI_2), Vec3::splat(3.0));
+
+        let lerp = a.lerp(&b, 0.5);
+        let slerp = a.slerp(&b, 0.5);
+
+        assert_near(lerp.translation, Vec3::new(1.0, 0.0, 0.0));
+        assert_near(lerp.scale, Vec3::splat(2.0));
+        assert_near(slerp.euler_angles(), Vec3::new(0.0, FRAC_PI_2 / 2.0, 0.0));
+        assert_near(lerp.euler_angles(), slerp.euler_angles());
+    }
+}
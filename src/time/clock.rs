@@ -0,0 +1,231 @@
+use core::time::Duration;
+
+use super::instant::Instant;
+
+// upper bound of `Time::scale`, keeping scaled delta within `Duration` range
+const MAX_SCALE: f32 = 1000.0;
+
+// frame timing, updated once per frame before systems run
+pub struct Time {
+    delta: Duration,
+    raw_delta: Duration,
+    elapsed: Duration,
+    frame_count: u64,
+    scale: f32,
+    paused: bool,
+    last_update: Option<Instant>,
+}
+
+impl Time {
+    pub fn new() -> Self {
+        Self {
+            delta: Duration::ZERO,
+            raw_delta: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            frame_count: 0,
+            scale: 1.0,
+            paused: false,
+            last_update: None,
+        }
+    }
+
+    // time since last frame, scaled and zero while paused
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    // time since last frame regardless of scale and pause
+    pub fn raw_delta(&self) -> Duration {
+        self.raw_delta
+    }
+
+    // sum of scaled deltas
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn elapsed_seconds(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    // clamped to [0, `MAX_SCALE`], NaN is treated as 0
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = if scale.is_nan() { 0.0 } else { scale.clamp(0.0, MAX_SCALE) };
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub(super) fn update(&mut self) {
+        let now = Instant::now();
+        let raw_delta = self.last_update.map(|x| now.duration_since(x)).unwrap_or_default();
+        self.last_update = Some(now);
+
+        self.advance(raw_delta);
+    }
+
+    pub(super) fn advance(&mut self, raw_delta: Duration) {
+        self.raw_delta = raw_delta;
+        self.delta = if self.paused { Duration::ZERO } else { raw_delta.mul_f32(self.scale) };
+        self.elapsed += self.delta;
+        self.frame_count += 1;
+    }
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// accumulates scaled frame time and runs `Stage::FixedUpdate` once for each whole `step` accumulated
+pub struct FixedTime {
+    step: Duration,
+    accumulator: Duration,
+    max_steps: u32,
+}
+
+impl FixedTime {
+    pub fn new(step: Duration) -> Self {
+        assert!(!step.is_zero(), "fixed timestep should not be zero");
+
+        Self {
+            step,
+            accumulator: Duration::ZERO,
+            max_steps: 8,
+        }
+    }
+
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    pub fn step_seconds(&self) -> f32 {
+        self.step.as_secs_f32()
+    }
+
+    pub fn max_steps(&self) -> u32 {
+        self.max_steps
+    }
+
+    // limits fixed updates run in a frame. time beyond the limit is dropped, so slow frames don't pile up more steps.
+    pub fn set_max_steps(&mut self, max_steps: u32) {
+        self.max_steps = max_steps;
+    }
+
+    // fraction of step left in accumulator, for interpolating between fixed updates
+    pub fn overstep_fraction(&self) -> f32 {
+        self.accumulator.as_secs_f32() / self.step.as_secs_f32()
+    }
+
+    pub(crate) fn accumulate(&mut self, delta: Duration) {
+        self.accumulator = self.accumulator.saturating_add(delta);
+    }
+
+    // consumes whole steps from accumulator and returns their count, up to `max_steps`
+    pub(crate) fn expend(&mut self) -> u32 {
+        let steps = u32::try_from(self.accumulator.as_nanos() / self.step.as_nanos()).unwrap_or(u32::MAX);
+        if steps > self.max_steps {
+            log::debug!("skipping {} fixed steps", steps - self.max_steps);
+
+            self.accumulator = Duration::from_nanos((self.accumulator.as_nanos() % self.step.as_nanos()) as u64);
+
+            return self.max_steps;
+        }
+
+        self.accumulator -= self.step * steps;
+
+        steps
+    }
+}
+
+impl Default for FixedTime {
+    fn default() -> Self {
+        Self::new(Duration::from_secs_f64(1.0 / 60.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_time() {
+        let mut time = Time::new();
+
+        time.advance(Duration::from_millis(10));
+        assert_eq!(time.delta(), Duration::from_millis(10));
+
+        time.set_scale(2.0);
+        time.advance(Duration::from_millis(10));
+        assert_eq!(time.delta(), Duration::from_millis(20));
+        assert_eq!(time.elapsed(), Duration::from_millis(30));
+
+        time.pause();
+        time.advance(Duration::from_millis(10));
+        assert_eq!(time.delta(), Duration::ZERO);
+        assert_eq!(time.raw_delta(), Duration::from_millis(10));
+        assert_eq!(time.elapsed(), Duration::from_millis(30));
+        assert_eq!(time.frame_count(), 3);
+    }
+
+    #[test]
+    fn test_time_scale_clamp() {
+        let mut time = Time::new();
+
+        time.set_scale(f32::INFINITY);
+        assert_eq!(time.scale(), MAX_SCALE);
+        time.advance(Duration::from_millis(10));
+        assert_eq!(time.delta(), Duration::from_secs(10));
+
+        time.set_scale(f32::NAN);
+        assert_eq!(time.scale(), 0.0);
+
+        time.set_scale(-1.0);
+        assert_eq!(time.scale(), 0.0);
+    }
+
+    #[test]
+    fn test_fixed_time() {
+        let mut fixed_time = FixedTime::new(Duration::from_millis(10));
+
+        fixed_time.accumulate(Duration::from_millis(25));
+        assert_eq!(fixed_time.expend(), 2);
+        assert!((fixed_time.overstep_fraction() - 0.5).abs() < 1e-5);
+
+        fixed_time.accumulate(Duration::from_millis(4));
+        assert_eq!(fixed_time.expend(), 0);
+
+        fixed_time.accumulate(Duration::from_millis(1));
+        assert_eq!(fixed_time.expend(), 1);
+
+        fixed_time.set_max_steps(3);
+        fixed_time.accumulate(Duration::from_millis(55));
+        assert_eq!(fixed_time.expend(), 3);
+        assert!((fixed_time.overstep_fraction() - 0.5).abs() < 1e-5);
+
+        fixed_time.accumulate(Duration::MAX);
+        assert_eq!(fixed_time.expend(), 3);
+    }
+}
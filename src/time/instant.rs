@@ -0,0 +1,38 @@
+use core::time::Duration;
+
+// std::time::Instant panics on wasm32, so use clock of javascript there
+#[derive(Clone, Copy)]
+pub(crate) struct Instant {
+    #[cfg(target_arch = "wasm32")]
+    millis: f64,
+    #[cfg(not(target_arch = "wasm32"))]
+    instant: std::time::Instant,
+}
+
+impl Instant {
+    pub fn now() -> Self {
+        #[cfg(target_arch = "wasm32")]
+        {
+            Self { millis: js_sys::Date::now() }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Self {
+                instant: std::time::Instant::now(),
+            }
+        }
+    }
+
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        #[cfg(target_arch = "wasm32")]
+        {
+            Duration::from_secs_f64((self.millis - earlier.millis).max(0.0) / 1000.0)
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.instant.duration_since(earlier.instant)
+        }
+    }
+}
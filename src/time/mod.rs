@@ -0,0 +1,81 @@
+mod clock;
+mod instant;
+mod timer;
+
+pub use clock::{FixedTime, Time};
+pub use timer::{DelayedCommandsExt, Stopwatch, Timer, TimerFinished, TimerMode};
+
+use crate::{
+    ecs::{IntoSystemDescriptor, Stage, World},
+    App, Plugin,
+};
+
+pub struct TimePlugin;
+
+impl Plugin for TimePlugin {
+    fn build(&self, app: &mut App) {
+        let world = app.world_mut();
+
+        init(world);
+        world.add_system_to_stage(Stage::PreUpdate, update.label("time"));
+    }
+}
+
+pub(crate) fn init(world: &mut World) {
+    world.add_resource(Time::new());
+    world.add_resource(FixedTime::default());
+    world.add_resource(timer::DelayedCommands::default());
+    world.add_event::<TimerFinished>();
+
+    world.add_system_to_stage(Stage::PreUpdate, timer::tick_timers.after("time"));
+    world.set_stage_run_count(Stage::FixedUpdate, fixed_steps);
+}
+
+// runs `Stage::FixedUpdate` once for each whole fixed timestep accumulated
+fn fixed_steps(world: &mut World) -> u32 {
+    world.resource_mut::<FixedTime>().map_or(0, |x| x.expend())
+}
+
+// advances time by elapsed wall clock time, once per frame before other systems
+fn update(world: &mut World) {
+    let time = world.resource_mut::<Time>().unwrap();
+    time.update();
+
+    let delta = time.delta();
+    if let Some(fixed_time) = world.resource_mut::<FixedTime>() {
+        fixed_time.accumulate(delta);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::time::Duration;
+
+    use super::*;
+    use crate::ecs::{CommandList, ResMut};
+
+    struct Counter {
+        fixed_updates: u32,
+    }
+
+    #[tokio::test]
+    async fn test_fixed_update_stage() {
+        let mut world = World::new();
+        init(&mut world);
+        world.add_resource(FixedTime::new(Duration::from_millis(10)));
+        world.add_resource(Counter { fixed_updates: 0 });
+
+        world.add_system_to_stage(Stage::FixedUpdate, |mut counter: ResMut<Counter>| {
+            counter.get_mut().fixed_updates += 1;
+
+            CommandList::new()
+        });
+
+        world.resource_mut::<FixedTime>().unwrap().accumulate(Duration::from_millis(35));
+        world.update().await;
+        assert_eq!(world.resource::<Counter>().unwrap().fixed_updates, 3);
+
+        world.update().await;
+        assert_eq!(world.resource::<Counter>().unwrap().fixed_updates, 3);
+    }
+}
@@ -0,0 +1,349 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::time::Duration;
+
+use super::Time;
+use crate::ecs::{Command, CommandList, Component, Entity, World};
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum TimerMode {
+    Once,
+    Repeating,
+}
+
+// counts down scaled time(see `Time`). ticked every frame when added as component or resource,
+// sending `TimerFinished` when it finishes.
+#[derive(Clone, Debug)]
+pub struct Timer {
+    duration: Duration,
+    elapsed: Duration,
+    mode: TimerMode,
+    paused: bool,
+    finished: bool,
+    times_finished_this_tick: u32,
+}
+
+impl Timer {
+    pub fn new(duration: Duration, mode: TimerMode) -> Self {
+        Self {
+            duration,
+            elapsed: Duration::ZERO,
+            mode,
+            paused: false,
+            finished: false,
+            times_finished_this_tick: 0,
+        }
+    }
+
+    pub fn once(duration: Duration) -> Self {
+        Self::new(duration, TimerMode::Once)
+    }
+
+    pub fn repeating(duration: Duration) -> Self {
+        Self::new(duration, TimerMode::Repeating)
+    }
+
+    // advances timer and returns how many times it finished
+    pub fn tick(&mut self, delta: Duration) -> u32 {
+        self.times_finished_this_tick = 0;
+        if self.paused || (self.mode == TimerMode::Once && self.finished) {
+            return 0;
+        }
+
+        self.elapsed += delta;
+        if self.elapsed >= self.duration {
+            self.times_finished_this_tick = match self.mode {
+                TimerMode::Once => {
+                    self.elapsed = self.duration;
+                    self.finished = true;
+
+                    1
+                }
+                TimerMode::Repeating if self.duration.is_zero() => {
+                    self.elapsed = Duration::ZERO;
+
+                    1
+                }
+                TimerMode::Repeating => {
+                    let times = (self.elapsed.as_nanos() / self.duration.as_nanos()) as u32;
+                    self.elapsed -= self.duration * times;
+
+                    times
+                }
+            };
+        }
+
+        self.times_finished_this_tick
+    }
+
+    // ticking paused or finished one-shot timer doesn't change it, once its last finish is cleared
+    pub(super) fn is_idle(&self) -> bool {
+        self.times_finished_this_tick == 0 && (self.paused || (self.mode == TimerMode::Once && self.finished))
+    }
+
+    // one-shot timer stays finished once finished. repeating timer is finished only on the tick it wrapped around.
+    pub fn finished(&self) -> bool {
+        match self.mode {
+            TimerMode::Once => self.finished,
+            TimerMode::Repeating => self.just_finished(),
+        }
+    }
+
+    pub fn just_finished(&self) -> bool {
+        self.times_finished_this_tick > 0
+    }
+
+    pub fn times_finished_this_tick(&self) -> u32 {
+        self.times_finished_this_tick
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.elapsed)
+    }
+
+    // elapsed fraction of duration, in 0.0..=1.0
+    pub fn fraction(&self) -> f32 {
+        if self.duration.is_zero() {
+            1.0
+        } else {
+            self.elapsed.as_secs_f32() / self.duration.as_secs_f32()
+        }
+    }
+
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.finished = false;
+        self.times_finished_this_tick = 0;
+    }
+}
+
+impl Component for Timer {}
+
+// counts up scaled time. ticked every frame when added as component or resource.
+#[derive(Clone, Default, Debug)]
+pub struct Stopwatch {
+    elapsed: Duration,
+    paused: bool,
+}
+
+impl Stopwatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        if !self.paused {
+            self.elapsed += delta;
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn elapsed_seconds(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+    }
+}
+
+impl Component for Stopwatch {}
+
+// sent when `Timer` finishes, once for each time it finished in a tick. `entity` is none for `Timer` resource.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct TimerFinished {
+    pub entity: Option<Entity>,
+}
+
+pub trait DelayedCommandsExt {
+    // applies `commands` once `delay` of scaled time(see `Time`) has passed
+    fn after(self, delay: Duration, commands: CommandList) -> Self;
+}
+
+impl DelayedCommandsExt for CommandList {
+    fn after(self, delay: Duration, commands: CommandList) -> Self {
+        self.add(move |world: &mut World| {
+            if world.resource::<DelayedCommands>().is_none() {
+                world.add_resource(DelayedCommands::default());
+            }
+
+            world.resource_mut::<DelayedCommands>().unwrap().push(delay, commands);
+        })
+    }
+}
+
+// commands scheduled with `DelayedCommandsExt::after`
+#[derive(Default)]
+pub(super) struct DelayedCommands {
+    pending: Vec<(Duration, CommandList)>,
+}
+
+impl DelayedCommands {
+    pub fn push(&mut self, delay: Duration, commands: CommandList) {
+        self.pending.push((delay, commands));
+    }
+
+    // returns commands whose delay has passed, in scheduled order
+    fn tick(&mut self, delta: Duration) -> Vec<Box<dyn Command>> {
+        let mut result = Vec::new();
+
+        let mut index = 0;
+        while index < self.pending.len() {
+            let remaining = &mut self.pending[index].0;
+            *remaining = remaining.saturating_sub(delta);
+
+            if remaining.is_zero() {
+                result.extend(self.pending.remove(index).1.commands);
+            } else {
+                index += 1;
+            }
+        }
+
+        result
+    }
+}
+
+pub(super) fn tick_timers(world: &mut World) {
+    let delta = world.resource::<Time>().map(|x| x.delta()).unwrap_or_default();
+
+    // idle timers are skipped so they aren't marked as changed
+    let mut finished = world
+        .components_mut::<Timer>()
+        .filter(|(_, timer)| !timer.is_idle())
+        .map(|(entity, mut timer)| (Some(entity), timer.tick(delta)))
+        .collect::<Vec<_>>();
+    if let Some(x) = world.resource_mut::<Timer>() {
+        finished.push((None, x.tick(delta)));
+    }
+
+    for (entity, times) in finished {
+        for _ in 0..times {
+            world.send_event(TimerFinished { entity });
+        }
+    }
+
+    for (_, mut stopwatch) in world.components_mut::<Stopwatch>().filter(|(_, x)| !x.is_paused()) {
+        stopwatch.tick(delta);
+    }
+    if let Some(x) = world.resource_mut::<Stopwatch>() {
+        x.tick(delta);
+    }
+
+    let commands = world.resource_mut::<DelayedCommands>().map(|x| x.tick(delta)).unwrap_or_default();
+    world.run_commands(commands);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecs::Events;
+
+    #[test]
+    fn test_timer() {
+        let mut timer = Timer::once(Duration::from_millis(10));
+
+        assert_eq!(timer.tick(Duration::from_millis(6)), 0);
+        assert_eq!(timer.tick(Duration::from_millis(6)), 1);
+        assert!(timer.finished());
+        assert_eq!(timer.elapsed(), Duration::from_millis(10));
+        assert_eq!(timer.tick(Duration::from_millis(6)), 0);
+        assert!(timer.finished() && !timer.just_finished());
+
+        let mut timer = Timer::repeating(Duration::from_millis(10));
+
+        assert_eq!(timer.tick(Duration::from_millis(25)), 2);
+        assert_eq!(timer.elapsed(), Duration::from_millis(5));
+        assert_eq!(timer.tick(Duration::from_millis(1)), 0);
+        assert!(!timer.finished());
+
+        timer.pause();
+        assert_eq!(timer.tick(Duration::from_millis(10)), 0);
+        assert_eq!(timer.elapsed(), Duration::from_millis(6));
+    }
+
+    #[tokio::test]
+    async fn test_timer_event() {
+        let mut world = World::new();
+        crate::time::init(&mut world);
+
+        let entity = world.spawn().with(Timer::once(Duration::ZERO)).entity();
+        world.update().await;
+
+        let events = world.resource::<Events<TimerFinished>>().unwrap();
+        assert!(events.iter().eq([&TimerFinished { entity: Some(entity) }]));
+    }
+
+    #[tokio::test]
+    async fn test_repeating_timer_event() {
+        let mut world = World::new();
+        crate::time::init(&mut world);
+        world.add_resource(Timer::repeating(Duration::from_millis(10)));
+
+        world.resource_mut::<Time>().unwrap().advance(Duration::from_millis(25));
+        world.update().await;
+
+        let events = world.resource::<Events<TimerFinished>>().unwrap();
+        assert!(events.iter().eq([&TimerFinished { entity: None }, &TimerFinished { entity: None }]));
+    }
+
+    #[tokio::test]
+    async fn test_delayed_commands() {
+        struct TestComponent {}
+        impl Component for TestComponent {}
+
+        let mut world = World::new();
+        crate::time::init(&mut world);
+
+        let entity = world.spawn().with(TestComponent {}).entity();
+        world.add_system_to_stage(crate::ecs::Stage::Startup, move |_: &World| {
+            CommandList::new().after(Duration::from_millis(20), CommandList::new().destroy_entity(entity))
+        });
+        world.run_stage(crate::ecs::Stage::Startup).await;
+
+        world.resource_mut::<Time>().unwrap().advance(Duration::from_millis(10));
+        world.update().await;
+        assert!(world.is_alive(entity));
+
+        world.resource_mut::<Time>().unwrap().advance(Duration::from_millis(10));
+        world.update().await;
+        assert!(!world.is_alive(entity));
+    }
+}
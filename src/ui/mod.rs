@@ -4,9 +4,20 @@ use glam::Vec3;
 
 use super::{
     ecs::{Component, ComponentBundle, ComponentContainer, Entity, World},
-    render::{AssetLoader, Material, Mesh, RenderBundle, Renderer, SimpleVertex, TextureAsset, Transform},
+    render::{AssetLoader, AssetPlugin, Material, Mesh, RenderBundle, RenderPlugin, Renderer, SimpleVertex, TextureAsset, Transform},
+    App, Plugin,
 };
 
+// ui is drawn by renderer with ui camera, using textures from asset loader
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_plugin(AssetPlugin);
+        app.insert_plugin(RenderPlugin);
+    }
+}
+
 pub struct UiComponent {}
 impl Component for UiComponent {}
 